@@ -2,12 +2,15 @@ use clap::Parser;
 
 #[derive(Parser)]
 struct Args {
-    #[clap(required = true, value_parser = intervalle::TimeSpec::parse, allow_hyphen_values = true)]
-    intervalle: intervalle::TimeSpec,
+    #[command(flatten)]
+    interval: intervalle::IntervalArgs,
 }
 
 fn main() {
     let cli = Args::parse();
 
-    println!("Parsed: {:?}", cli.intervalle)
+    match cli.interval.interval() {
+        Ok(interval) => println!("Parsed: {interval}"),
+        Err(e) => eprintln!("{e}"),
+    }
 }
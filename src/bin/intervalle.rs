@@ -0,0 +1,243 @@
+//! Evaluate timespecs from the shell, e.g. `intervalle yesterday` or `intervalle -r -2h..`
+
+use clap::{Parser, Subcommand, ValueEnum};
+use intervalle::{
+    Clock, Dialect, FixedClock, Interval, IntervalleError, SystemClock, TimeSpec, Zone,
+};
+use std::{error::Error, process::ExitCode};
+use time::{
+    format_description::well_known::Rfc3339, OffsetDateTime, PrimitiveDateTime as DateTime,
+    UtcOffset,
+};
+
+#[derive(Parser)]
+#[command(
+    version,
+    about = "Evaluate systemd timespecs and ranges",
+    args_conflicts_with_subcommands = true
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// The timespec to evaluate, e.g. `yesterday`, `-2h` or `2024-08-08 14:10 UTC`
+    #[arg(default_value = "now", allow_hyphen_values = true)]
+    timespec: String,
+
+    /// Read ranges such as `yesterday..today`, printing their ends separated by a tab
+    #[arg(short, long, global = true)]
+    range: bool,
+
+    /// Resolve relative timespecs against this time instead of the current time
+    #[arg(
+        long,
+        global = true,
+        value_name = "TIMESPEC",
+        allow_hyphen_values = true
+    )]
+    anchor: Option<String>,
+
+    /// The timezone of timespecs given without one, and of the output [default: the system's]
+    #[arg(long, global = true, value_name = "TIMEZONE", value_parser = zone)]
+    tz: Option<Zone>,
+
+    /// Also accept the date strings of GNU `date -d`, such as `last monday` or `1 month ago`
+    #[arg(long, global = true, conflicts_with = "range")]
+    gnu: bool,
+
+    /// How to print the result
+    #[arg(long, global = true, value_enum, default_value_t = Format::Rfc3339)]
+    format: Format,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Only check that timespecs are valid, reporting those that are not
+    Check {
+        #[arg(required = true, allow_hyphen_values = true)]
+        timespecs: Vec<String>,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    /// An RFC 3339 timestamp, e.g. `2024-08-08T14:10:11+02:00`
+    Rfc3339,
+    /// Seconds since the Unix epoch, e.g. `1723119011`
+    Epoch,
+    /// The canonical form of the instant, in the `--tz` zone or at the system's offset, e.g.
+    /// `2024-08-08 14:10:11 Europe/Paris`
+    Timespec,
+}
+
+impl Cli {
+    fn dialect(&self) -> Dialect {
+        if self.gnu {
+            Dialect::Gnu
+        } else {
+            Dialect::Systemd
+        }
+    }
+}
+
+/// Parse `timespec` in the dialect chosen, against the anchor of `clock`
+fn parse(cli: &Cli, clock: &FixedClock, timespec: &str) -> Result<TimeSpec, IntervalleError> {
+    TimeSpec::parse_with_dialect(timespec, clock.anchor(), cli.dialect())
+}
+
+/// A timezone given as `UTC` or a name from the IANA database, checked to load
+fn zone(name: &str) -> Result<Zone, String> {
+    let zone = match name {
+        "UTC" | "Z" => Zone::Fixed(UtcOffset::UTC),
+        name => Zone::Named(name.to_owned()),
+    };
+
+    zone.time_zone().map(|_| zone).map_err(|e| e.to_string())
+}
+
+/// The clock timespecs are resolved against, stopped at the anchor
+fn clock(cli: &Cli) -> Result<FixedClock, Box<dyn Error>> {
+    let local = match &cli.tz {
+        Some(zone) => zone.time_zone()?,
+        None => SystemClock.local()?,
+    };
+    let clock = FixedClock::new(OffsetDateTime::now_utc(), local.clone());
+
+    match &cli.anchor {
+        Some(anchor) => Ok(FixedClock::new(
+            parse(cli, &clock, anchor)?.resolve_in(&clock)?,
+            local,
+        )),
+        None => Ok(clock),
+    }
+}
+
+/// Seconds since the Unix epoch, with the fraction of a second if any, e.g. `-0.5`
+fn epoch(instant: OffsetDateTime) -> String {
+    let nanoseconds = instant.unix_timestamp_nanos();
+    let sign = if nanoseconds < 0 { "-" } else { "" };
+    let seconds = nanoseconds.unsigned_abs() / 1_000_000_000;
+
+    match nanoseconds.unsigned_abs() % 1_000_000_000 {
+        0 => format!("{sign}{seconds}"),
+        fraction => format!(
+            "{sign}{seconds}.{}",
+            format!("{fraction:09}").trim_end_matches('0')
+        ),
+    }
+}
+
+/// Write `instant` in `format`, at the offset observed at that instant in `local`, whose name
+/// is `zone` if known
+fn display(
+    instant: OffsetDateTime,
+    format: Format,
+    local: &tz::TimeZone,
+    zone: Option<&Zone>,
+) -> Result<String, Box<dyn Error>> {
+    let offset = local
+        .find_local_time_type(instant.unix_timestamp())?
+        .ut_offset();
+    let instant = instant.to_offset(UtcOffset::from_whole_seconds(offset)?);
+
+    match format {
+        Format::Epoch => Ok(epoch(instant)),
+        Format::Rfc3339 => Ok(instant.format(&Rfc3339)?),
+        Format::Timespec => Ok(TimeSpec::Point(
+            DateTime::new(instant.date(), instant.time()),
+            zone.cloned().unwrap_or(Zone::Fixed(instant.offset())),
+        )
+        .to_string()),
+    }
+}
+
+/// Print the instant `timespec` designates, or the ends of the range
+fn evaluate(cli: &Cli, clock: &FixedClock, timespec: &str) -> Result<(), Box<dyn Error>> {
+    let local = clock.local()?;
+    let display = |instant| display(instant, cli.format, &local, cli.tz.as_ref());
+
+    if cli.range {
+        let interval = Interval::parse_range_in(clock, timespec)?;
+        let end = |instant: Option<OffsetDateTime>| instant.map_or(Ok(String::new()), display);
+        println!("{}\t{}", end(interval.start())?, end(interval.end())?);
+    } else {
+        let timespec = parse(cli, clock, timespec)?;
+        println!("{}", display(timespec.resolve_in(clock)?)?);
+    }
+
+    Ok(())
+}
+
+/// Parse every one of `timespecs`, reporting the invalid ones
+fn check(cli: &Cli, clock: &FixedClock, timespecs: &[String]) -> ExitCode {
+    let mut code = ExitCode::SUCCESS;
+
+    for timespec in timespecs {
+        let checked = if cli.range {
+            Interval::parse_range_in(clock, timespec).map(drop)
+        } else {
+            parse(cli, clock, timespec).map(drop)
+        };
+
+        if let Err(error) = checked {
+            eprint!("{}", error.report());
+            code = ExitCode::FAILURE;
+        }
+    }
+
+    code
+}
+
+fn run(cli: &Cli) -> Result<ExitCode, Box<dyn Error>> {
+    let clock = clock(cli)?;
+
+    match &cli.command {
+        Some(Command::Check { timespecs }) => Ok(check(cli, &clock, timespecs)),
+        None => evaluate(cli, &clock, &cli.timespec).map(|()| ExitCode::SUCCESS),
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    run(&cli).unwrap_or_else(|error| {
+        match error.downcast_ref::<IntervalleError>() {
+            Some(error) => eprint!("{}", error.report()),
+            None => eprintln!("error: {error}"),
+        }
+        ExitCode::FAILURE
+    })
+}
+
+#[test]
+fn test_epoch() {
+    for (nanoseconds, expected) in [
+        (1_723_119_011_000_000_000, "1723119011"),
+        (1_250_000_000, "1.25"),
+        (0, "0"),
+        (-500_000_000, "-0.5"),
+        (-1_000_000_000, "-1"),
+        (-1_000_000_001, "-1.000000001"),
+    ] {
+        assert_eq!(
+            epoch(OffsetDateTime::from_unix_timestamp_nanos(nanoseconds).unwrap()),
+            expected
+        );
+    }
+}
+
+#[test]
+fn test_display_timespec() {
+    let instant = OffsetDateTime::from_unix_timestamp(1_723_119_011).unwrap();
+    let paris = Zone::Named(String::from("Europe/Paris"));
+    let local = paris.time_zone().unwrap();
+
+    assert_eq!(
+        display(instant, Format::Timespec, &local, Some(&paris)).unwrap(),
+        "2024-08-08 14:10:11 Europe/Paris"
+    );
+    assert_eq!(
+        display(instant, Format::Timespec, &local, None).unwrap(),
+        "2024-08-08 14:10:11 +02:00"
+    );
+}
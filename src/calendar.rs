@@ -0,0 +1,997 @@
+use crate::{
+    error::{Failure, FailureKind, CALENDAR},
+    parser::{self, validate},
+    zone::{instant_offset, locate},
+    IntervalleError, Resolution, Zone,
+};
+use std::fmt;
+use time::{Date, Duration, Month, OffsetDateTime, PrimitiveDateTime as DateTime};
+use winnow::{
+    ascii::{digit1, space1, Caseless},
+    combinator::{alt, not, opt, peek, preceded, separated, terminated},
+    error::{ContextError, StrContext},
+    prelude::*,
+    token::{one_of, take_while},
+};
+
+const USEC_PER_SEC: u32 = 1_000_000;
+
+/// The name and bounds of a field of a calendar event
+struct Field {
+    name: &'static str,
+    min: u32,
+    max: u32,
+    /// Units of the field per unit written, fractions being allowed when above one
+    scale: u32,
+    /// Digits values are padded to when written
+    width: usize,
+    /// Applied to the values given for the field
+    fixup: fn(u32) -> u32,
+}
+
+const YEAR: Field = Field {
+    name: "year",
+    min: 1970,
+    max: 9999,
+    scale: 1,
+    width: 4,
+    fixup: |year| match year {
+        0..=69 => year + 2000,
+        70..=99 => year + 1900,
+        _ => year,
+    },
+};
+const MONTH: Field = Field {
+    name: "month",
+    min: 1,
+    max: 12,
+    scale: 1,
+    width: 2,
+    fixup: |month| month,
+};
+const DAY: Field = Field {
+    name: "day",
+    min: 1,
+    max: 31,
+    scale: 1,
+    width: 2,
+    fixup: |day| day,
+};
+const HOUR: Field = Field {
+    name: "hour",
+    min: 0,
+    max: 23,
+    scale: 1,
+    width: 2,
+    fixup: |hour| hour,
+};
+const MINUTE: Field = Field {
+    name: "minute",
+    min: 0,
+    max: 59,
+    scale: 1,
+    width: 2,
+    fixup: |minute| minute,
+};
+/// Seconds are counted in microseconds
+const SECOND: Field = Field {
+    name: "second",
+    min: 0,
+    max: 60 * USEC_PER_SEC - 1,
+    scale: USEC_PER_SEC,
+    width: 2,
+    fixup: |second| second,
+};
+
+/// A `start[..stop][/repeat]` element of a calendar component
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy)]
+struct Chunk {
+    start: u32,
+    stop: Option<u32>,
+    repeat: Option<u32>,
+}
+
+impl Chunk {
+    /// The last value matched, repetitions running up to `max` unless stopped earlier
+    fn last(&self, max: u32) -> u32 {
+        match self.repeat {
+            Some(_) => self.stop.unwrap_or(max),
+            None => self.stop.unwrap_or(self.start),
+        }
+    }
+
+    fn matches(&self, value: u32, max: u32) -> bool {
+        (self.start..=self.last(max)).contains(&value)
+            && self
+                .repeat
+                .is_none_or(|repeat| (value - self.start).is_multiple_of(repeat))
+    }
+
+    /// Whether the `value`th day from the end of the month is matched
+    ///
+    /// As in systemd, the bounds of ranges are swapped: `~01..07` matches the last seven days.
+    /// Repetitions run towards the end of the month, from the day furthest from it.
+    fn matches_from_end(&self, value: u32) -> bool {
+        let (first, last) = match (self.stop, self.repeat) {
+            (Some(stop), _) => (stop, self.start),
+            (None, Some(_)) => (self.start, 1),
+            (None, None) => (self.start, self.start),
+        };
+
+        (last..=first).contains(&value)
+            && self
+                .repeat
+                .is_none_or(|repeat| (first - value).is_multiple_of(repeat))
+    }
+
+    /// The smallest value matched from `value` on
+    fn next(&self, value: u32, max: u32) -> Option<u32> {
+        let candidate = match self.repeat {
+            _ if value <= self.start => self.start,
+            Some(repeat) => self.start + (value - self.start).div_ceil(repeat) * repeat,
+            None => value,
+        };
+
+        (candidate <= self.last(max).min(max)).then_some(candidate)
+    }
+}
+
+/// The values a field of a calendar event matches, all of them when empty
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+struct Component(Vec<Chunk>);
+
+impl Component {
+    fn value(value: u32) -> Self {
+        Component(vec![Chunk {
+            start: value,
+            stop: None,
+            repeat: None,
+        }])
+    }
+
+    fn values(values: &[u32]) -> Self {
+        Component(
+            values
+                .iter()
+                .copied()
+                .map(Component::value)
+                .flat_map(|c| c.0)
+                .collect(),
+        )
+    }
+
+    fn matches(&self, value: u32, max: u32) -> bool {
+        self.0.is_empty() || self.0.iter().any(|chunk| chunk.matches(value, max))
+    }
+
+    fn matches_from_end(&self, value: u32) -> bool {
+        self.0.is_empty() || self.0.iter().any(|chunk| chunk.matches_from_end(value))
+    }
+
+    /// The smallest value matched from `value` on, up to `max`
+    fn next(&self, value: u32, max: u32) -> Option<u32> {
+        if self.0.is_empty() {
+            return (value <= max).then_some(value);
+        }
+
+        self.0
+            .iter()
+            .filter_map(|chunk| chunk.next(value, max))
+            .min()
+    }
+
+    /// Write the component the way systemd normalizes it, e.g. `01,03..05/2`
+    fn write(&self, f: &mut fmt::Formatter, field: &Field) -> Result<(), fmt::Error> {
+        let width = field.width;
+        let precision = field.scale.ilog10() as usize;
+        let value = |f: &mut fmt::Formatter, value: u32, width: usize| {
+            write!(f, "{:0width$}", value / field.scale)?;
+            match value % field.scale {
+                0 => Ok(()),
+                fraction => write!(f, ".{fraction:0precision$}"),
+            }
+        };
+
+        if self.0.is_empty() {
+            return write!(f, "*");
+        }
+
+        for (i, chunk) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            value(f, chunk.start, width)?;
+            if let Some(stop) = chunk.stop {
+                write!(f, "..")?;
+                value(f, stop, width)?;
+            }
+            if let Some(repeat) = chunk.repeat {
+                write!(f, "/")?;
+                value(f, repeat, 0)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A calendar event as given to `OnCalendar=` in systemd timers, e.g. `Mon..Fri *-*-* 09:00`
+///
+/// The syntax is described in systemd.time(7): optional weekdays, a date and a time whose
+/// fields may be lists of values, ranges or repetitions, and an optional timezone. The
+/// shorthands `minutely`, `hourly`, `daily`, `weekly`, `monthly`, `quarterly`,
+/// `semiannually`, `yearly` and `annually` are also understood.
+#[derive(PartialEq, Debug, Clone)]
+pub struct CalendarEvent {
+    /// The weekdays the event is restricted to as bits from Monday, or 0 for any day
+    weekdays: u8,
+    year: Component,
+    month: Component,
+    day: Component,
+    /// Days are counted from the end of the month, as written with `~`
+    end_of_month: bool,
+    hour: Component,
+    minute: Component,
+    second: Component,
+    zone: Zone,
+}
+
+/// A part of a calendar event, in the order they are written
+enum Part {
+    Weekdays(u8),
+    Date(Component, Component, bool, Component),
+    Time(Component, Component, Option<Component>),
+    Zone(Zone),
+}
+
+impl Part {
+    fn rank(&self) -> u8 {
+        match self {
+            Part::Weekdays(_) => 0,
+            Part::Date(..) => 1,
+            Part::Time(..) => 2,
+            Part::Zone(_) => 3,
+        }
+    }
+}
+
+impl CalendarEvent {
+    /// Every day at midnight
+    fn daily() -> Self {
+        CalendarEvent {
+            weekdays: 0,
+            year: Component::default(),
+            month: Component::default(),
+            day: Component::default(),
+            end_of_month: false,
+            hour: Component::value(0),
+            minute: Component::value(0),
+            second: Component::value(0),
+            zone: Zone::Local,
+        }
+    }
+
+    fn from_parts(parts: Vec<Part>) -> Option<Self> {
+        let ordered = parts.windows(2).all(|pair| pair[0].rank() < pair[1].rank());
+        if !ordered || matches!(parts[..], [Part::Zone(_)]) {
+            return None;
+        }
+
+        let mut event = CalendarEvent::daily();
+        for part in parts {
+            match part {
+                // Every day of the week is the same as any day, as systemd normalizes it
+                Part::Weekdays(0x7f) => (),
+                Part::Weekdays(weekdays) => event.weekdays = weekdays,
+                Part::Date(year, month, end_of_month, day) => {
+                    event.year = year;
+                    event.month = month;
+                    event.end_of_month = end_of_month;
+                    event.day = day;
+                }
+                Part::Time(hour, minute, second) => {
+                    event.hour = hour;
+                    event.minute = minute;
+                    event.second = second.unwrap_or(Component::value(0));
+                }
+                Part::Zone(zone) => event.zone = zone,
+            }
+        }
+
+        Some(event)
+    }
+
+    pub fn parse(event: &str) -> Result<Self, IntervalleError> {
+        parser::complete(calendar(), event, &CALENDAR)
+    }
+
+    /// The timezone the event is expressed in
+    pub fn zone(&self) -> &Zone {
+        &self.zone
+    }
+
+    fn matches_day(&self, date: Date) -> bool {
+        let weekday = date.weekday().number_days_from_monday();
+        let day = u32::from(date.day());
+
+        (self.weekdays == 0 || self.weekdays & (1 << weekday) != 0)
+            && match self.end_of_month {
+                true => self
+                    .day
+                    .matches_from_end(u32::from(date.month().length(date.year())) - day + 1),
+                false => self.day.matches(day, DAY.max),
+            }
+    }
+
+    /// The first wall clock time at or after `from` that the event matches
+    fn next_match(&self, from: DateTime) -> Option<DateTime> {
+        let mut t = from;
+
+        loop {
+            let year = u32::try_from(t.year()).ok()?;
+            match self.year.next(year, YEAR.max)? {
+                next if next != year => {
+                    t = Date::from_calendar_date(i32::try_from(next).ok()?, Month::January, 1)
+                        .ok()?
+                        .midnight();
+                    continue;
+                }
+                _ => (),
+            }
+
+            let month = u32::from(u8::from(t.month()));
+            match self.month.next(month, MONTH.max) {
+                None => {
+                    t = Date::from_calendar_date(t.year() + 1, Month::January, 1)
+                        .ok()?
+                        .midnight();
+                    continue;
+                }
+                Some(next) if next != month => {
+                    let next = Month::try_from(u8::try_from(next).ok()?).ok()?;
+                    t = Date::from_calendar_date(t.year(), next, 1).ok()?.midnight();
+                    continue;
+                }
+                Some(_) => (),
+            }
+
+            let first = t.date().replace_day(1).ok()?;
+            let next_month =
+                first.checked_add(Duration::days(t.month().length(t.year()).into()))?;
+            match (t.day()..=t.month().length(t.year()))
+                .map(|day| t.date().replace_day(day))
+                .find_map(|date| date.ok().filter(|date| self.matches_day(*date)))
+            {
+                None => {
+                    t = next_month.midnight();
+                    continue;
+                }
+                Some(date) if date != t.date() => {
+                    t = date.midnight();
+                    continue;
+                }
+                Some(_) => (),
+            }
+
+            let hour = u32::from(t.hour());
+            match self.hour.next(hour, HOUR.max) {
+                None => {
+                    t = t.date().next_day()?.midnight();
+                    continue;
+                }
+                Some(next) if next != hour => {
+                    t = t.date().with_hms(u8::try_from(next).ok()?, 0, 0).ok()?;
+                    continue;
+                }
+                Some(_) => (),
+            }
+
+            let minute = u32::from(t.minute());
+            match self.minute.next(minute, MINUTE.max) {
+                None => {
+                    t = t
+                        .date()
+                        .with_hms(t.hour(), 0, 0)
+                        .ok()?
+                        .checked_add(Duration::HOUR)?;
+                    continue;
+                }
+                Some(next) if next != minute => {
+                    t = t
+                        .date()
+                        .with_hms(t.hour(), u8::try_from(next).ok()?, 0)
+                        .ok()?;
+                    continue;
+                }
+                Some(_) => (),
+            }
+
+            let second = u32::from(t.second()) * USEC_PER_SEC + t.microsecond();
+            match self.second.next(second, SECOND.max) {
+                None => {
+                    t = t
+                        .date()
+                        .with_hms(t.hour(), t.minute(), 0)
+                        .ok()?
+                        .checked_add(Duration::MINUTE)?;
+                }
+                Some(next) => {
+                    return t
+                        .date()
+                        .with_hms_micro(
+                            t.hour(),
+                            t.minute(),
+                            u8::try_from(next / USEC_PER_SEC).ok()?,
+                            next % USEC_PER_SEC,
+                        )
+                        .ok()
+                }
+            }
+        }
+    }
+
+    /// The instants at which the event elapses strictly after `after`, with `local` used for
+    /// events in `Zone::Local`
+    pub fn next_elapse_with(
+        &self,
+        after: OffsetDateTime,
+        local: &tz::TimeZone,
+    ) -> Result<Elapses<'_>, IntervalleError> {
+        let time_zone = match &self.zone {
+            Zone::Local => local.clone(),
+            zone => zone
+                .time_zone()
+                .map_err(|e| IntervalleError::TimeZoneError(e.into()))?,
+        };
+
+        Ok(Elapses {
+            event: self,
+            time_zone,
+            after,
+        })
+    }
+
+    /// The instants at which the event elapses strictly after `after`, using the system's
+    /// timezone for `Zone::Local`
+    pub fn next_elapse(&self, after: OffsetDateTime) -> Result<Elapses<'_>, IntervalleError> {
+        let local = tz::TimeZone::local().map_err(|e| IntervalleError::TimeZoneError(e.into()))?;
+
+        self.next_elapse_with(after, &local)
+    }
+}
+
+/// Writes the event the way `systemd-analyze calendar` normalizes it, e.g.
+/// `Mon..Fri *-*-* 09:00:00` for `mon,tue..fri 9:00`
+impl fmt::Display for CalendarEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        const DAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+        if self.weekdays != 0 {
+            // Runs of three days or more are written as ranges
+            let mut first = true;
+            let mut day = 0;
+            while day < DAYS.len() {
+                if self.weekdays & 1 << day == 0 {
+                    day += 1;
+                    continue;
+                }
+
+                let start = day;
+                while day + 1 < DAYS.len() && self.weekdays & 1 << (day + 1) != 0 {
+                    day += 1;
+                }
+
+                if !first {
+                    write!(f, ",")?;
+                }
+                first = false;
+
+                match day - start {
+                    0 => write!(f, "{}", DAYS[start])?,
+                    1 => write!(f, "{},{}", DAYS[start], DAYS[day])?,
+                    _ => write!(f, "{}..{}", DAYS[start], DAYS[day])?,
+                }
+                day += 1;
+            }
+            write!(f, " ")?;
+        }
+
+        self.year.write(f, &YEAR)?;
+        write!(f, "-")?;
+        self.month.write(f, &MONTH)?;
+        write!(f, "{}", if self.end_of_month { '~' } else { '-' })?;
+        self.day.write(f, &DAY)?;
+        write!(f, " ")?;
+        self.hour.write(f, &HOUR)?;
+        write!(f, ":")?;
+        self.minute.write(f, &MINUTE)?;
+        write!(f, ":")?;
+        self.second.write(f, &SECOND)?;
+
+        self.zone.write_suffix(f)
+    }
+}
+
+/// The successive elapses of a `CalendarEvent`
+///
+/// Wall clock times skipped by a DST transition never elapse, and those repeated by one
+/// elapse once, at their earliest instant.
+pub struct Elapses<'a> {
+    event: &'a CalendarEvent,
+    time_zone: tz::TimeZone,
+    after: OffsetDateTime,
+}
+
+impl Iterator for Elapses<'_> {
+    type Item = OffsetDateTime;
+
+    fn next(&mut self) -> Option<OffsetDateTime> {
+        let offset = instant_offset(&self.time_zone, self.after.unix_timestamp()).ok()?;
+        let local = self.after.to_offset(offset);
+        let mut from = DateTime::new(
+            local.date(),
+            local.time().replace_microsecond(local.microsecond()).ok()?,
+        )
+        .checked_add(Duration::MICROSECOND)?;
+
+        loop {
+            let candidate = self.event.next_match(from)?;
+            from = candidate.checked_add(Duration::MICROSECOND)?;
+
+            let instant = match locate(&self.time_zone, candidate).ok()? {
+                Resolution::Single(instant) => instant,
+                Resolution::Ambiguous(earliest, _) if earliest > self.after => earliest,
+                Resolution::Ambiguous(_, latest) => latest,
+                Resolution::Nonexistent(..) => continue,
+            };
+
+            if instant > self.after {
+                self.after = instant;
+                return Some(instant);
+            }
+        }
+    }
+}
+
+/// A value of `field`, in the units of the field
+fn value<'i>(field: &'static Field) -> impl Parser<&'i str, u32, ContextError> {
+    (digit1, opt(preceded(".", digit1))).verify_map(move |(int, frac): (&str, Option<&str>)| {
+        let int = int.parse::<u32>().ok()?;
+
+        match (field.scale, frac) {
+            (1, None) => Some(int),
+            (1, Some(_)) => None,
+            (scale, frac) => {
+                // Round to the nearest unit, from one digit more than the scale has
+                let precision = scale.ilog10() as usize + 1;
+                let digits: String = frac
+                    .unwrap_or_default()
+                    .chars()
+                    .chain(std::iter::repeat('0'))
+                    .take(precision)
+                    .collect();
+                let frac = (digits.parse::<u32>().ok()? + 5) / 10;
+
+                int.checked_mul(scale)?.checked_add(frac)
+            }
+        }
+    })
+}
+
+/// A `start[..stop][/repeat]` element, where `start` may be `*` when repeated
+fn chunk<'i>(field: &'static Field) -> impl Parser<&'i str, Chunk, ContextError> {
+    validate(
+        (
+            alt(("*".value(None), value(field).map(Some))),
+            opt(preceded("..", value(field))),
+            opt(preceded("/", value(field))),
+        )
+            .with_taken(),
+        move |((start, stop, repeat), taken)| {
+            let invalid = || {
+                Failure::new(
+                    FailureKind::InvalidCalendarValue(field.name),
+                    0..taken.len(),
+                )
+            };
+
+            let start = match (start, stop, repeat) {
+                (Some(start), ..) => (field.fixup)(start),
+                (None, None, Some(_)) => field.min,
+                (None, ..) => return Err(invalid()),
+            };
+            let stop = stop.map(field.fixup);
+
+            if !(field.min..=field.max).contains(&start)
+                || stop.is_some_and(|stop| stop < start || stop > field.max)
+                || repeat == Some(0)
+            {
+                return Err(invalid());
+            }
+
+            Ok(Chunk {
+                start,
+                stop,
+                repeat,
+            })
+        },
+    )
+}
+
+/// `*` or a comma-separated list of chunks
+fn component<'i>(field: &'static Field) -> impl Parser<&'i str, Component, ContextError> {
+    alt((
+        terminated("*", not("/")).value(Component::default()),
+        separated(1.., chunk(field), ",").map(|mut chunks: Vec<Chunk>| {
+            chunks.sort();
+            chunks.dedup();
+            Component(chunks)
+        }),
+    ))
+    .context(StrContext::Label(field.name))
+}
+
+/// The text of a component, used to look ahead
+fn raw<'i>() -> impl Parser<&'i str, &'i str, ContextError> {
+    take_while(1.., |c: char| c.is_ascii_digit() || "*,./".contains(c))
+}
+
+/// A comma-separated list of weekdays and ranges of weekdays, as bits from Monday
+fn weekdays<'i>() -> impl Parser<&'i str, u8, ContextError> {
+    terminated(
+        separated(
+            1..,
+            validate(
+                (weekday!(), opt(preceded("..", weekday!()))).with_taken(),
+                |((first, last), taken)| {
+                    let first = first.number_days_from_monday();
+                    let last =
+                        last.map_or(first, |last: time::Weekday| last.number_days_from_monday());
+
+                    match first <= last {
+                        true => Ok((first..=last).fold(0u8, |bits, day| bits | 1 << day)),
+                        false => Err(Failure::new(
+                            FailureKind::InvalidCalendarValue("weekday range"),
+                            0..taken.len(),
+                        )),
+                    }
+                },
+            ),
+            ",",
+        )
+        .map(|days: Vec<u8>| days.into_iter().fold(0, |bits, day| bits | day)),
+        opt(","),
+    )
+}
+
+/// `year-month-day` or `month-day`, with `~` in place of the last `-` to count days from
+/// the end of the month
+fn date<'i>() -> impl Parser<&'i str, Part, ContextError> {
+    let day_separator = || alt(("-".value(false), "~".value(true)));
+
+    alt((
+        preceded(
+            peek((raw(), "-", raw(), one_of(['-', '~']))),
+            (
+                component(&YEAR),
+                preceded("-", component(&MONTH)),
+                day_separator(),
+                component(&DAY),
+            ),
+        )
+        .map(|(year, month, end_of_month, day)| Part::Date(year, month, end_of_month, day)),
+        preceded(
+            peek((raw(), one_of(['-', '~']), raw())),
+            (component(&MONTH), day_separator(), component(&DAY)),
+        )
+        .map(|(month, end_of_month, day)| {
+            Part::Date(Component::default(), month, end_of_month, day)
+        }),
+    ))
+}
+
+/// `hour:minute[:second]`
+fn time<'i>() -> impl Parser<&'i str, Part, ContextError> {
+    preceded(
+        peek((raw(), ":")),
+        (
+            component(&HOUR),
+            preceded(":", component(&MINUTE)),
+            opt(preceded(":", component(&SECOND))),
+        ),
+    )
+    .map(|(hour, minute, second)| Part::Time(hour, minute, second))
+}
+
+fn shorthand<'i>() -> impl Parser<&'i str, CalendarEvent, ContextError> {
+    let daily = CalendarEvent::daily;
+
+    alt((
+        "minutely".map(move |_| CalendarEvent {
+            hour: Component::default(),
+            minute: Component::default(),
+            ..daily()
+        }),
+        "hourly".map(move |_| CalendarEvent {
+            hour: Component::default(),
+            ..daily()
+        }),
+        "daily".map(move |_| daily()),
+        "weekly".map(move |_| CalendarEvent {
+            weekdays: 1,
+            ..daily()
+        }),
+        "monthly".map(move |_| CalendarEvent {
+            day: Component::value(1),
+            ..daily()
+        }),
+        "quarterly".map(move |_| CalendarEvent {
+            month: Component::values(&[1, 4, 7, 10]),
+            day: Component::value(1),
+            ..daily()
+        }),
+        "semiannually".map(move |_| CalendarEvent {
+            month: Component::values(&[1, 7]),
+            day: Component::value(1),
+            ..daily()
+        }),
+        alt(("yearly", "annually")).map(move |_| CalendarEvent {
+            month: Component::value(1),
+            day: Component::value(1),
+            ..daily()
+        }),
+    ))
+}
+
+/// A complete calendar event
+fn calendar<'i>() -> impl Parser<&'i str, CalendarEvent, ContextError> {
+    alt((
+        (shorthand(), opt(preceded(space1, zone!()))).map(|(event, zone)| CalendarEvent {
+            zone: zone.unwrap_or_default(),
+            ..event
+        }),
+        separated(
+            1..,
+            alt((
+                weekdays().map(Part::Weekdays),
+                date(),
+                time(),
+                zone!().map(Part::Zone),
+            )),
+            space1,
+        )
+        .verify_map(CalendarEvent::from_parts),
+    ))
+    .context(StrContext::Label("calendar event"))
+}
+
+#[test]
+fn test_calendar_equivalent() {
+    for (event, normalized) in [
+        ("daily", "*-*-* 00:00:00"),
+        ("hourly", "*-*-* *:00:00"),
+        ("minutely", "*-*-* *:*:00"),
+        ("weekly", "Mon *-*-* 00:00"),
+        ("monthly", "*-*-01"),
+        ("quarterly", "*-01,04,07,10-01 00:00:00"),
+        ("semiannually", "*-1,7-1"),
+        ("annually", "yearly"),
+        (
+            "Sat,Thu,Mon..Wed,Sat..Sun",
+            "Mon..Thu,Sat,Sun *-*-* 00:00:00",
+        ),
+        ("Wed..Wed,Wed *-1", "Wed *-*-01 00:00:00"),
+        ("Wed, 17:48", "Wed *-*-* 17:48:00"),
+        ("Mon,Sun 12-*-* 2,1:23", "Mon,Sun 2012-*-* 01,02:23:00"),
+        ("12,14,13,12:20,10,30", "*-*-* 12,13,14:10,20,30:00"),
+        ("10-15", "*-10-15 00:00:00"),
+        ("monday *-12-* 17:00", "Mon *-12-* 17:00:00"),
+        ("daily UTC", "*-*-* 00:00:00 UTC"),
+    ] {
+        assert_eq!(
+            CalendarEvent::parse(event).unwrap(),
+            CalendarEvent::parse(normalized).unwrap(),
+            "{event}"
+        );
+    }
+}
+
+#[test]
+fn test_calendar_invalid() {
+    for event in ["", "UTC", "daily daily", "*-*-* 00:00 Mon", "Fri..Mon"] {
+        assert!(CalendarEvent::parse(event).is_err(), "{event}");
+    }
+
+    for (event, field, text) in [
+        ("*-13-01", "month", "13"),
+        ("*-*-* 24:00", "hour", "24"),
+        ("*-*-* 12:00/0", "minute", "00/0"),
+        ("*-*-05..01", "day", "05..01"),
+        ("Sat..Mon 12:00", "weekday range", "Sat..Mon"),
+    ] {
+        match CalendarEvent::parse(event) {
+            Err(IntervalleError::InvalidCalendarValue { field: f, span }) => {
+                assert_eq!((f, span.text()), (field, text), "{event}")
+            }
+            other => panic!("unexpected result for {event}: {other:?}"),
+        }
+    }
+
+    match CalendarEvent::parse("fortnightly") {
+        Err(IntervalleError::UnknownKeyword { expected, .. }) => {
+            assert_eq!(expected, crate::error::SHORTHANDS)
+        }
+        other => panic!("unexpected result: {other:?}"),
+    }
+}
+
+#[test]
+fn test_next_elapse() {
+    let after = time::Date::from_calendar_date(2024, time::Month::August, 8)
+        .unwrap()
+        .with_hms(12, 0, 0)
+        .unwrap()
+        .assume_utc();
+
+    let elapses = |event: &str, count| -> Vec<String> {
+        CalendarEvent::parse(event)
+            .unwrap()
+            .next_elapse_with(after, &tz::TimeZone::utc())
+            .unwrap()
+            .take(count)
+            .map(|t| format!("{} {}", t.date(), t.time()))
+            .collect()
+    };
+
+    assert_eq!(
+        elapses("Mon..Fri *-*-* 09:00:00", 3),
+        [
+            "2024-08-09 9:00:00.0",
+            "2024-08-12 9:00:00.0",
+            "2024-08-13 9:00:00.0"
+        ]
+    );
+    assert_eq!(
+        elapses("2024-*-* 12:00/15", 5),
+        [
+            "2024-08-08 12:15:00.0",
+            "2024-08-08 12:30:00.0",
+            "2024-08-08 12:45:00.0",
+            "2024-08-09 12:00:00.0",
+            "2024-08-09 12:15:00.0"
+        ]
+    );
+    assert_eq!(
+        elapses("*-*-01 00:00", 2),
+        ["2024-09-01 0:00:00.0", "2024-10-01 0:00:00.0"]
+    );
+    assert_eq!(
+        elapses("*-02~01", 2),
+        ["2025-02-28 0:00:00.0", "2026-02-28 0:00:00.0"]
+    );
+    assert_eq!(
+        elapses("Mon *-05~07/1 10:00", 2),
+        ["2025-05-26 10:00:00.0", "2026-05-25 10:00:00.0"]
+    );
+    assert_eq!(
+        elapses("*-*~01..07", 3),
+        [
+            "2024-08-25 0:00:00.0",
+            "2024-08-26 0:00:00.0",
+            "2024-08-27 0:00:00.0"
+        ]
+    );
+    assert_eq!(
+        elapses("*-*~02..06/2", 4),
+        [
+            "2024-08-26 0:00:00.0",
+            "2024-08-28 0:00:00.0",
+            "2024-08-30 0:00:00.0",
+            "2024-09-25 0:00:00.0"
+        ]
+    );
+    assert_eq!(elapses("12:00:00.5", 1), ["2024-08-08 12:00:00.5"]);
+    assert_eq!(elapses("2024-08-08 12:00", 1), Vec::<String>::new());
+}
+
+#[test]
+fn test_next_elapse_dst() {
+    let paris = tz::TimeZone::from_posix_tz("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+    let event = CalendarEvent::parse("*-*-* 02:30").unwrap();
+
+    let elapses = |after: time::PrimitiveDateTime| -> Vec<String> {
+        event
+            .next_elapse_with(after.assume_utc(), &paris)
+            .unwrap()
+            .take(2)
+            .map(|t| t.to_offset(time::UtcOffset::UTC))
+            .map(|t| format!("{} {}", t.date(), t.time()))
+            .collect()
+    };
+
+    // 2024-03-31 02:30 does not exist in Paris
+    let after = time::Date::from_calendar_date(2024, time::Month::March, 30)
+        .unwrap()
+        .with_hms(12, 0, 0)
+        .unwrap();
+    assert_eq!(
+        elapses(after),
+        ["2024-04-01 0:30:00.0", "2024-04-02 0:30:00.0"]
+    );
+
+    // 2024-10-27 02:30 happens twice in Paris, the event elapses at the first
+    let after = time::Date::from_calendar_date(2024, time::Month::October, 26)
+        .unwrap()
+        .with_hms(12, 0, 0)
+        .unwrap();
+    assert_eq!(
+        elapses(after),
+        ["2024-10-27 0:30:00.0", "2024-10-28 1:30:00.0"]
+    );
+}
+
+#[test]
+fn test_calendar_display() {
+    // The examples of systemd.time(7), as normalized by `systemd-analyze calendar`
+    for (event, normalized) in [
+        (
+            "Sat,Thu,Mon..Wed,Sat..Sun",
+            "Mon..Thu,Sat,Sun *-*-* 00:00:00",
+        ),
+        ("Mon,Sun 12-*-* 2,1:23", "Mon,Sun 2012-*-* 01,02:23:00"),
+        ("Wed *-1", "Wed *-*-01 00:00:00"),
+        ("Wed..Wed,Wed *-1", "Wed *-*-01 00:00:00"),
+        ("Wed, 17:48", "Wed *-*-* 17:48:00"),
+        (
+            "Wed..Sat,Tue 12-10-15 1:2:3",
+            "Tue..Sat 2012-10-15 01:02:03",
+        ),
+        ("*-*-7 0:0:0", "*-*-07 00:00:00"),
+        ("10-15", "*-10-15 00:00:00"),
+        ("monday *-12-* 17:00", "Mon *-12-* 17:00:00"),
+        ("Mon,Fri *-*-3,1,2 *:30:45", "Mon,Fri *-*-01,02,03 *:30:45"),
+        ("12,14,13,12:20,10,30", "*-*-* 12,13,14:10,20,30:00"),
+        ("12..14:10,20,30", "*-*-* 12..14:10,20,30:00"),
+        ("mon,fri *-1/2-1,3 *:30:45", "Mon,Fri *-01/2-01,03 *:30:45"),
+        ("03-05 08:05:40", "*-03-05 08:05:40"),
+        ("08:05:40", "*-*-* 08:05:40"),
+        ("05:40", "*-*-* 05:40:00"),
+        ("Sat,Sun 12-05 08:05:40", "Sat,Sun *-12-05 08:05:40"),
+        ("Sat,Sun 08:05:40", "Sat,Sun *-*-* 08:05:40"),
+        ("2003-03-05 05:40", "2003-03-05 05:40:00"),
+        (
+            "05:40:23.4200004/3.1700005",
+            "*-*-* 05:40:23.420000/3.170001",
+        ),
+        ("2003-02..04-05", "2003-02..04-05 00:00:00"),
+        ("2003-03-05 05:40 UTC", "2003-03-05 05:40:00 UTC"),
+        ("2003-03-05", "2003-03-05 00:00:00"),
+        ("03-05", "*-03-05 00:00:00"),
+        ("hourly", "*-*-* *:00:00"),
+        ("daily", "*-*-* 00:00:00"),
+        ("daily UTC", "*-*-* 00:00:00 UTC"),
+        ("monthly", "*-*-01 00:00:00"),
+        ("weekly", "Mon *-*-* 00:00:00"),
+        (
+            "weekly Pacific/Auckland",
+            "Mon *-*-* 00:00:00 Pacific/Auckland",
+        ),
+        ("yearly", "*-01-01 00:00:00"),
+        ("annually", "*-01-01 00:00:00"),
+        ("*:2/3", "*-*-* *:02/3:00"),
+        ("minutely", "*-*-* *:*:00"),
+        ("quarterly", "*-01,04,07,10-01 00:00:00"),
+        ("semiannually", "*-01,07-01 00:00:00"),
+        ("*-02~03", "*-02~03 00:00:00"),
+        ("Mon *-05~07/1", "Mon *-05~07/1 00:00:00"),
+        ("Mon..Sun 12:00 +02:00", "*-*-* 12:00:00 +02:00"),
+        ("Mon..Fri,Sat,Sun 12:00", "*-*-* 12:00:00"),
+    ] {
+        let parsed = CalendarEvent::parse(event).unwrap();
+        assert_eq!(parsed.to_string(), normalized, "{event}");
+
+        let reparsed = CalendarEvent::parse(normalized).unwrap();
+        assert_eq!(reparsed, parsed, "{event}");
+        assert_eq!(reparsed.to_string(), normalized, "{event}");
+    }
+}
@@ -0,0 +1,244 @@
+//! Integration with clap, for command-line arguments taking timespecs
+
+use crate::{
+    error::{FORMATS, KEYWORDS},
+    Anchor, Clock, Interval, IntervalleError, SystemClock, TimeSpec,
+};
+use ::clap::{
+    builder::{PossibleValue, TypedValueParser, ValueParserFactory},
+    error::{ContextKind, ContextValue, ErrorKind},
+    Arg, ArgAction, ArgGroup, ArgMatches, Args, Command, FromArgMatches, Id, ValueHint,
+};
+use std::ffi::OsStr;
+
+/// Parses arguments into `TimeSpec`s
+///
+/// Timespecs starting with `-` look like flags to clap: arguments using this parser need
+/// `Arg::allow_hyphen_values`, which the arguments of `IntervalArgs` set. The keywords are
+/// offered as hidden possible values for shell completions, without restricting the timespecs
+/// accepted.
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub struct TimeSpecValueParser {
+    anchor: Option<Anchor>,
+}
+
+impl TimeSpecValueParser {
+    /// A parser resolving relative forms against the system's clock at the time of parsing
+    pub fn new() -> Self {
+        TimeSpecValueParser::default()
+    }
+
+    /// A parser resolving relative forms against `anchor`
+    pub fn with_anchor(anchor: impl Into<Anchor>) -> Self {
+        TimeSpecValueParser {
+            anchor: Some(anchor.into()),
+        }
+    }
+
+    fn parse(&self, timespec: &str) -> Result<TimeSpec, IntervalleError> {
+        match self.anchor {
+            Some(anchor) => TimeSpec::parse_with_anchor(timespec, anchor),
+            None => TimeSpec::parse(timespec),
+        }
+    }
+}
+
+impl TypedValueParser for TimeSpecValueParser {
+    type Value = TimeSpec;
+
+    fn parse_ref(
+        &self,
+        cmd: &Command,
+        arg: Option<&Arg>,
+        value: &OsStr,
+    ) -> Result<TimeSpec, ::clap::Error> {
+        let value = value
+            .to_str()
+            .ok_or_else(|| ::clap::Error::new(ErrorKind::InvalidUtf8).with_cmd(cmd))?;
+
+        self.parse(value).map_err(|error| {
+            let arg = arg.map_or_else(|| String::from("..."), Arg::to_string);
+            let mut error = ::clap::Error::raw(
+                ErrorKind::ValueValidation,
+                format!("invalid value '{value}' for '{arg}': {error}"),
+            )
+            .with_cmd(cmd);
+
+            error.insert(ContextKind::InvalidArg, ContextValue::String(arg));
+            error.insert(
+                ContextKind::InvalidValue,
+                ContextValue::String(value.to_owned()),
+            );
+            error
+        })
+    }
+
+    fn possible_values(&self) -> Option<Box<dyn Iterator<Item = PossibleValue> + '_>> {
+        Some(Box::new(KEYWORDS.into_iter().map(|keyword| {
+            PossibleValue::new(keyword)
+                .help(match keyword {
+                    "now" => "The current time",
+                    "today" => "00:00:00 of the current day",
+                    "yesterday" => "00:00:00 of the day before the current day",
+                    _ => "00:00:00 of the day after the current day",
+                })
+                .hide(true)
+        })))
+    }
+}
+
+impl ValueParserFactory for TimeSpec {
+    type Parser = TimeSpecValueParser;
+
+    fn value_parser() -> TimeSpecValueParser {
+        TimeSpecValueParser::new()
+    }
+}
+
+/// The `--since` and `--until` arguments of journalctl, to flatten into a clap command
+///
+/// Both bounds are parsed against the same anchor, taken when the arguments are added to the
+/// command. They are grouped under the id `interval`.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct IntervalArgs {
+    pub since: Option<TimeSpec>,
+    pub until: Option<TimeSpec>,
+}
+
+impl IntervalArgs {
+    /// The interval between the bounds given, checking that `since` does not come after `until`
+    pub fn interval(&self) -> Result<Interval, IntervalleError> {
+        Interval::new(self.since.clone(), self.until.clone())
+    }
+}
+
+/// The long help of an argument taking a timespec: `summary` then the accepted formats
+fn long_help(summary: &str) -> String {
+    let mut help = format!("{summary}\n\nAccepted formats:");
+    for format in FORMATS {
+        help.push_str("\n  ");
+        help.push_str(format);
+    }
+    help
+}
+
+/// An argument taking a timespec
+fn timespec_arg(id: &'static str, short: char, summary: &'static str, anchor: Anchor) -> Arg {
+    Arg::new(id)
+        .short(short)
+        .long(id)
+        .value_name("DATE")
+        .action(ArgAction::Set)
+        .value_parser(TimeSpecValueParser::with_anchor(anchor))
+        .allow_hyphen_values(true)
+        .hide_possible_values(true)
+        .value_hint(ValueHint::Other)
+        .help(summary)
+        .long_help(long_help(summary))
+}
+
+impl Args for IntervalArgs {
+    fn group_id() -> Option<Id> {
+        Some(Id::from("interval"))
+    }
+
+    fn augment_args(cmd: Command) -> Command {
+        let anchor = Anchor::from(SystemClock.anchor());
+
+        cmd.arg(timespec_arg(
+            "since",
+            'S',
+            "Show entries on or newer than the specified date",
+            anchor,
+        ))
+        .arg(timespec_arg(
+            "until",
+            'U',
+            "Show entries on or older than the specified date",
+            anchor,
+        ))
+        .group(
+            ArgGroup::new("interval")
+                .args(["since", "until"])
+                .multiple(true),
+        )
+    }
+
+    fn augment_args_for_update(cmd: Command) -> Command {
+        IntervalArgs::augment_args(cmd)
+    }
+}
+
+impl FromArgMatches for IntervalArgs {
+    fn from_arg_matches(matches: &ArgMatches) -> Result<Self, ::clap::Error> {
+        let mut args = IntervalArgs::default();
+        args.update_from_arg_matches(matches)?;
+        Ok(args)
+    }
+
+    fn update_from_arg_matches(&mut self, matches: &ArgMatches) -> Result<(), ::clap::Error> {
+        if let Some(since) = matches.get_one::<TimeSpec>("since") {
+            self.since = Some(since.clone());
+        }
+        if let Some(until) = matches.get_one::<TimeSpec>("until") {
+            self.until = Some(until.clone());
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_interval_args() {
+    let matches = IntervalArgs::augment_args(Command::new("journal"))
+        .try_get_matches_from(["journal", "--since", "-15:28", "-U", "2024-08-08 UTC"])
+        .unwrap();
+    let args = IntervalArgs::from_arg_matches(&matches).unwrap();
+
+    assert!(matches!(args.since, Some(TimeSpec::Before(..))));
+    assert_eq!(
+        args.until,
+        Some(TimeSpec::Point(
+            time::Date::from_calendar_date(2024, time::Month::August, 8)
+                .unwrap()
+                .midnight(),
+            crate::Zone::Fixed(time::UtcOffset::UTC)
+        ))
+    );
+
+    let matches = IntervalArgs::augment_args(Command::new("journal"))
+        .try_get_matches_from(["journal"])
+        .unwrap();
+    let args = IntervalArgs::from_arg_matches(&matches).unwrap();
+    assert_eq!(args, IntervalArgs::default());
+    assert!(args.interval().is_ok());
+}
+
+#[test]
+fn test_value_parser_error() {
+    let error = IntervalArgs::augment_args(Command::new("journal"))
+        .try_get_matches_from(["journal", "--since", "yesturday"])
+        .unwrap_err();
+
+    assert_eq!(error.kind(), ErrorKind::ValueValidation);
+    assert_eq!(
+        error.get(ContextKind::InvalidValue),
+        Some(&ContextValue::String(String::from("yesturday")))
+    );
+}
+
+#[test]
+fn test_possible_values() {
+    let cmd = IntervalArgs::augment_args(Command::new("journal"));
+    let since = cmd.get_arguments().find(|arg| arg.get_id() == "since");
+    let values = since.unwrap().get_possible_values();
+
+    assert_eq!(
+        values
+            .iter()
+            .map(PossibleValue::get_name)
+            .collect::<Vec<_>>(),
+        KEYWORDS
+    );
+    assert!(values.iter().all(PossibleValue::is_hide_set));
+}
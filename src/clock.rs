@@ -0,0 +1,227 @@
+use crate::{zone::instant_offset, IntervalleError, Zone};
+use time::{Duration, OffsetDateTime, PrimitiveDateTime as DateTime, UtcOffset};
+
+/// The time relative timespecs are read against
+///
+/// An instant places relative spans such as `-2h` on the timeline: they move it by elapsed
+/// time, DST transitions included, and are expressed in UTC. A date and time alone is a
+/// reading of the local wall clock, to which spans are added as they are.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Anchor {
+    /// A date and time on the local wall clock
+    Wall(DateTime),
+    /// An instant, at the offset of the local wall clock at that instant
+    Instant(OffsetDateTime),
+}
+
+impl Anchor {
+    /// The date and time on the local wall clock, which dates and times of day are read on
+    pub fn wall(&self) -> DateTime {
+        match self {
+            Anchor::Wall(dtime) => *dtime,
+            Anchor::Instant(instant) => DateTime::new(instant.date(), instant.time()),
+        }
+    }
+
+    /// The date and time `span` after the anchor, with the zone it is expressed in
+    pub(crate) fn shift(&self, span: Duration) -> Option<(DateTime, Zone)> {
+        match self {
+            Anchor::Wall(dtime) => dtime.checked_add(span).map(|dtime| (dtime, Zone::Local)),
+            Anchor::Instant(instant) => instant
+                .checked_add(span)?
+                .checked_to_offset(UtcOffset::UTC)
+                .map(|utc| {
+                    (
+                        DateTime::new(utc.date(), utc.time()),
+                        Zone::Fixed(UtcOffset::UTC),
+                    )
+                }),
+        }
+    }
+
+    /// A clock stopped at the anchor, whose local timezone is the offset of the anchor, or UTC
+    /// for a wall clock time
+    pub(crate) fn clock(&self) -> Result<FixedClock, IntervalleError> {
+        match self {
+            Anchor::Wall(dtime) => Ok(FixedClock::utc(dtime.assume_utc())),
+            Anchor::Instant(instant) => tz::TimeZone::fixed(instant.offset().whole_seconds())
+                .map(|local| FixedClock::new(*instant, local))
+                .map_err(|e| IntervalleError::TimeZoneError(e.into())),
+        }
+    }
+}
+
+impl From<DateTime> for Anchor {
+    fn from(dtime: DateTime) -> Self {
+        Anchor::Wall(dtime)
+    }
+}
+
+impl From<OffsetDateTime> for Anchor {
+    fn from(instant: OffsetDateTime) -> Self {
+        Anchor::Instant(instant)
+    }
+}
+
+/// Supplies the current time and the local timezone that relative timespecs and
+/// `Zone::Local` depend on
+pub trait Clock {
+    /// The current instant
+    fn now(&self) -> OffsetDateTime;
+
+    /// The timezone `Zone::Local` stands for
+    fn local(&self) -> Result<tz::TimeZone, IntervalleError>;
+
+    /// The current instant at the offset of the local timezone, which relative timespecs are
+    /// anchored to
+    ///
+    /// The time in UTC is used if the local timezone cannot be loaded.
+    fn anchor(&self) -> OffsetDateTime {
+        let now = self.now();
+        let offset = self
+            .local()
+            .and_then(|local| instant_offset(&local, now.unix_timestamp()))
+            .unwrap_or(UtcOffset::UTC);
+
+        now.to_offset(offset)
+    }
+}
+
+/// The system's clock and timezone
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> OffsetDateTime {
+        OffsetDateTime::now_utc()
+    }
+
+    fn local(&self) -> Result<tz::TimeZone, IntervalleError> {
+        tz::TimeZone::local().map_err(|e| IntervalleError::TimeZoneError(e.into()))
+    }
+}
+
+/// A clock stopped at a given instant, in a given local timezone
+#[derive(PartialEq, Debug, Clone)]
+pub struct FixedClock {
+    now: OffsetDateTime,
+    local: tz::TimeZone,
+}
+
+impl FixedClock {
+    pub fn new(now: OffsetDateTime, local: tz::TimeZone) -> Self {
+        FixedClock { now, local }
+    }
+
+    /// A clock stopped at `now`, with UTC as the local timezone
+    pub fn utc(now: OffsetDateTime) -> Self {
+        FixedClock::new(now, tz::TimeZone::utc())
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> OffsetDateTime {
+        self.now
+    }
+
+    fn local(&self) -> Result<tz::TimeZone, IntervalleError> {
+        Ok(self.local.clone())
+    }
+}
+
+#[test]
+fn test_fixed_clock() {
+    let date = time::Date::from_calendar_date(2024, time::Month::August, 8).unwrap();
+    let clock = FixedClock::utc(date.with_hms(23, 30, 0).unwrap().assume_utc());
+
+    assert_eq!(
+        crate::TimeSpec::parse_in(&clock, "today").unwrap(),
+        crate::TimeSpec::Point(date.midnight(), crate::Zone::Local)
+    );
+    assert_eq!(
+        crate::TimeSpec::parse_in(&clock, "-1h")
+            .unwrap()
+            .resolve_in(&clock)
+            .unwrap(),
+        date.with_hms(22, 30, 0).unwrap().assume_utc()
+    );
+}
+
+#[test]
+fn test_fixed_clock_local() {
+    let paris = tz::TimeZone::from_posix_tz("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+    let date = time::Date::from_calendar_date(2024, time::Month::August, 8).unwrap();
+    let clock = FixedClock::new(date.with_hms(23, 30, 0).unwrap().assume_utc(), paris);
+
+    assert_eq!(
+        clock.anchor().offset(),
+        time::UtcOffset::from_hms(2, 0, 0).unwrap()
+    );
+    assert_eq!(
+        Anchor::from(clock.anchor()).wall(),
+        date.next_day().unwrap().with_hms(1, 30, 0).unwrap()
+    );
+    assert_eq!(
+        crate::TimeSpec::parse_in(&clock, "today").unwrap(),
+        crate::TimeSpec::Point(date.next_day().unwrap().midnight(), crate::Zone::Local)
+    );
+    assert_eq!(
+        crate::TimeSpec::parse_in(&clock, "now")
+            .unwrap()
+            .resolve_in(&clock)
+            .unwrap(),
+        clock.now()
+    );
+}
+
+#[test]
+fn test_fixed_clock_dst() {
+    let paris = tz::TimeZone::from_posix_tz("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+    let at = |month, day, hour| {
+        time::Date::from_calendar_date(2024, month, day)
+            .unwrap()
+            .with_hms(hour, 30, 0)
+            .unwrap()
+            .assume_utc()
+    };
+
+    // Spans elapse across the transitions, rather than moving the local wall clock
+    for (now, timespec, expected) in [
+        (
+            at(time::Month::March, 31, 1),
+            "-2h",
+            at(time::Month::March, 30, 23),
+        ),
+        (
+            at(time::Month::March, 31, 1),
+            "2h ago",
+            at(time::Month::March, 30, 23),
+        ),
+        (
+            at(time::Month::March, 30, 23),
+            "+2h",
+            at(time::Month::March, 31, 1),
+        ),
+        (
+            at(time::Month::October, 27, 1),
+            "-1h",
+            at(time::Month::October, 27, 0),
+        ),
+        (
+            at(time::Month::October, 27, 1),
+            "now",
+            at(time::Month::October, 27, 1),
+        ),
+    ] {
+        let clock = FixedClock::new(now, paris.clone());
+
+        assert_eq!(
+            crate::TimeSpec::parse_in(&clock, timespec)
+                .unwrap()
+                .resolve_in(&clock)
+                .unwrap(),
+            expected,
+            "{timespec}"
+        );
+    }
+}
@@ -0,0 +1,675 @@
+use std::{error::Error, fmt, ops::Range};
+use time::{Month, OffsetDateTime, PrimitiveDateTime as DateTime, Weekday};
+use winnow::error::{ContextError, StrContext};
+
+/// Keywords accepted in place of a date
+pub(crate) const KEYWORDS: [&str; 4] = ["now", "today", "yesterday", "tomorrow"];
+
+/// Weekday names accepted in front of a date, in any case
+pub(crate) const WEEKDAYS: [&str; 14] = [
+    "monday",
+    "mon",
+    "tuesday",
+    "tue",
+    "wednesday",
+    "wed",
+    "thursday",
+    "thu",
+    "friday",
+    "fri",
+    "saturday",
+    "sat",
+    "sunday",
+    "sun",
+];
+
+/// Spellings of the time units accepted in a time span
+pub(crate) const UNITS: [&str; 30] = [
+    "usec", "us", "µs", "μs", "msec", "ms", "seconds", "second", "sec", "s", "minutes", "minute",
+    "min", "m", "hours", "hour", "hr", "h", "days", "day", "d", "weeks", "week", "w", "months",
+    "month", "M", "years", "year", "y",
+];
+
+/// Other words of the grammar
+pub(crate) const SUFFIXES: [&str; 5] = ["ago", "left", "to", "UTC", "Z"];
+
+/// Shorthands for common calendar events
+pub(crate) const SHORTHANDS: [&str; 9] = [
+    "minutely",
+    "hourly",
+    "daily",
+    "weekly",
+    "monthly",
+    "quarterly",
+    "semiannually",
+    "yearly",
+    "annually",
+];
+
+/// The formats accepted for a timespec, listed in help notes and argument help
+pub(crate) const FORMATS: [&str; 8] = [
+    "now, today, yesterday, tomorrow",
+    "YYYY-MM-DD [HH:MM[:SS[.fraction]]] [timezone]",
+    "YYYY-MM-DDTHH:MM:SS[.fraction](Z|+HH:MM)",
+    "<weekday> YYYY-MM-DD [HH:MM[:SS]]",
+    "HH:MM[:SS[.fraction]] [timezone]",
+    "@<seconds since the epoch>",
+    "+<span>, -<span>, <span> ago, <span> left",
+    "+<timestamp>, -<timestamp> for any time after or before it",
+];
+
+/// Examples of each form of timespec, offered as hints for invalid input
+pub(crate) const TIMESPEC_FORMS: [&str; 17] = [
+    "now",
+    "yesterday",
+    "-today",
+    "2024-08-08",
+    "2024-08-08 14:10",
+    "2024-08-08 14:10:11.5",
+    "2024-08-08 14:10:11 UTC",
+    "2024-08-08 14:10:11 Europe/Paris",
+    "2024-08-08T14:10:11+02:00",
+    "Thu 2024-08-08 14:10:11",
+    "14:10:11",
+    "+2024-08-08 14:10",
+    "@1723126211",
+    "-1h 30min",
+    "+2 days",
+    "5min ago",
+    "3h left",
+];
+
+/// Words of GNU date strings, besides the keywords and weekdays
+pub(crate) const GNU_WORDS: [&str; 22] = [
+    "years",
+    "year",
+    "months",
+    "month",
+    "fortnights",
+    "fortnight",
+    "weeks",
+    "week",
+    "days",
+    "day",
+    "hours",
+    "hour",
+    "minutes",
+    "minute",
+    "mins",
+    "seconds",
+    "second",
+    "secs",
+    "next",
+    "last",
+    "this",
+    "pm",
+];
+
+/// Examples of each form of GNU date string, offered as hints for invalid input
+pub(crate) const GNU_FORMS: [&str; 6] = [
+    "last monday",
+    "next week",
+    "2 days ago 14:00",
+    "1 month ago",
+    "tomorrow 9am",
+    "2024-08-08 14:10",
+];
+
+/// The formats accepted for a GNU date string, listed in help notes
+pub(crate) const GNU_FORMATS: [&str; 4] = [
+    "<timespec>",
+    "[last|this|next] <weekday>",
+    "[<number>|last|this|next] <unit> [ago]",
+    "<date> <time>[am|pm]",
+];
+
+/// The formats accepted for a range, listed in help notes
+pub(crate) const RANGE_FORMATS: [&str; 2] =
+    ["[<timespec>]..[<timespec>]", "<timespec> to <timespec>"];
+
+/// Examples of each form of range, offered as hints for invalid input
+pub(crate) const RANGE_FORMS: [&str; 5] = [
+    "yesterday..today",
+    "2024-08-01..2024-08-08",
+    "-2h..",
+    "..yesterday",
+    "10:00 to 11:30",
+];
+
+/// The formats accepted for a calendar event, listed in help notes
+pub(crate) const CALENDAR_FORMATS: [&str; 3] = [
+    "minutely, hourly, daily, weekly, monthly, quarterly, semiannually, yearly",
+    "[<weekdays>] [[YYYY-]MM-DD] [HH:MM[:SS[.fraction]]] [timezone]",
+    "YYYY-MM~DD for days counted from the end of the month",
+];
+
+/// Examples of each form of calendar event, offered as hints for invalid input
+pub(crate) const CALENDAR_FORMS: [&str; 7] = [
+    "daily",
+    "weekly Europe/Paris",
+    "Mon..Fri *-*-* 09:00:00",
+    "*-*-01 00:00",
+    "2024-*-* 12:00/15",
+    "Sat,Sun 10:00 UTC",
+    "*-02~01",
+];
+
+/// The formats accepted for a time span, listed in help notes
+pub(crate) const TIMESPAN_FORMATS: [&str; 3] =
+    ["infinity", "<number><unit> ..., e.g. 1h 30min", "<seconds>"];
+
+/// Examples of each form of time span, offered as hints for invalid input
+pub(crate) const TIMESPAN_FORMS: [&str; 5] = ["1h 30min", "2 days", "500ms", "90", "infinity"];
+
+/// What a grammar accepts, used to build hints for invalid input
+pub(crate) struct Grammar {
+    /// Examples of each accepted form
+    pub(crate) forms: &'static [&'static str],
+    /// The words of the grammar, in groups of alternatives
+    pub(crate) words: &'static [&'static [&'static str]],
+    /// The words accepted in place of a whole value, listed when an unknown word is met
+    pub(crate) keywords: &'static [&'static str],
+    /// The accepted formats, listed in help notes
+    pub(crate) formats: &'static [&'static str],
+}
+
+pub(crate) const TIMESPEC: Grammar = Grammar {
+    forms: &TIMESPEC_FORMS,
+    words: &[&KEYWORDS, &WEEKDAYS, &UNITS, &SUFFIXES],
+    keywords: &KEYWORDS,
+    formats: &FORMATS,
+};
+
+/// GNU date strings, falling back to the systemd grammar
+pub(crate) const GNU: Grammar = Grammar {
+    forms: &GNU_FORMS,
+    words: &[&KEYWORDS, &WEEKDAYS, &GNU_WORDS, &UNITS, &SUFFIXES],
+    keywords: &KEYWORDS,
+    formats: &GNU_FORMATS,
+};
+
+pub(crate) const RANGE: Grammar = Grammar {
+    forms: &RANGE_FORMS,
+    words: &[&KEYWORDS, &WEEKDAYS, &UNITS, &SUFFIXES],
+    keywords: &KEYWORDS,
+    formats: &RANGE_FORMATS,
+};
+
+pub(crate) const TIMESPAN: Grammar = Grammar {
+    forms: &TIMESPAN_FORMS,
+    words: &[&UNITS, &["infinity"]],
+    keywords: &["infinity"],
+    formats: &TIMESPAN_FORMATS,
+};
+
+pub(crate) const CALENDAR: Grammar = Grammar {
+    forms: &CALENDAR_FORMS,
+    words: &[&SHORTHANDS, &WEEKDAYS, &["UTC"]],
+    keywords: &SHORTHANDS,
+    formats: &CALENDAR_FORMATS,
+};
+
+/// Levenshtein distance between `a` and `b`, counted in characters
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+
+    row[b.len()]
+}
+
+/// The forms whose shape is closest to `input`, regardless of the value of digits
+pub(crate) fn closest_forms(input: &str, forms: &[&'static str]) -> Vec<&'static str> {
+    let shape = |s: &str| -> String {
+        s.chars()
+            .map(|c| match c {
+                '0'..='9' => '0',
+                c => c.to_ascii_lowercase(),
+            })
+            .collect()
+    };
+
+    let input = shape(input);
+    let distances: Vec<usize> = forms
+        .iter()
+        .map(|form| edit_distance(&input, &shape(form)))
+        .collect();
+    let closest = distances.iter().copied().min().unwrap_or_default();
+
+    forms
+        .iter()
+        .zip(distances)
+        .filter(|(_, distance)| *distance == closest)
+        .map(|(form, _)| *form)
+        .take(3)
+        .collect()
+}
+
+/// The first word of `input` that is not one of the words of `grammar` but is close to one
+/// of them
+pub(crate) fn misspelling(input: &str, grammar: &Grammar) -> Option<IntervalleError> {
+    let words = grammar.words;
+    let known = |word: &str| {
+        words.iter().any(|group| group.contains(&word))
+            || WEEKDAYS.iter().any(|day| day.eq_ignore_ascii_case(word))
+            || matches!(word, "UTC" | "Z")
+    };
+
+    input.split_whitespace().find_map(|token| {
+        let word = token.trim_start_matches(|c: char| c.is_ascii_digit() || "+-.@".contains(c));
+        if word.is_empty() || !word.chars().all(char::is_alphabetic) || known(word) {
+            return None;
+        }
+
+        let lowercase = &word.to_lowercase();
+        let threshold = word.chars().count() / 3;
+        let (distance, suggestion, expected) = words
+            .iter()
+            .flat_map(|group| {
+                group
+                    .iter()
+                    .map(move |term| (edit_distance(lowercase, &term.to_lowercase()), *term, group))
+            })
+            .min_by_key(|(distance, ..)| *distance)?;
+
+        if distance > threshold {
+            return None;
+        }
+
+        let end = token.as_ptr() as usize - input.as_ptr() as usize + token.len();
+
+        Some(IntervalleError::UnknownKeyword {
+            span: Span::new(input, end - word.len()..end, grammar),
+            expected: expected.to_vec(),
+            suggestion: Some(suggestion),
+        })
+    })
+}
+
+/// The part of the input an error refers to
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Span {
+    /// The complete input
+    pub input: String,
+    /// Byte range of the offending text within `input`
+    pub range: Range<usize>,
+    /// The formats accepted in place of `input`
+    pub formats: &'static [&'static str],
+}
+
+impl Span {
+    pub(crate) fn new(input: &str, range: Range<usize>, grammar: &Grammar) -> Self {
+        Span {
+            input: String::from(input),
+            range,
+            formats: grammar.formats,
+        }
+    }
+
+    /// The offending text
+    pub fn text(&self) -> &str {
+        &self.input[self.range.clone()]
+    }
+}
+
+/// A component of a time of day
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum TimeComponent {
+    Hour,
+    Minute,
+    Second,
+}
+
+impl fmt::Display for TimeComponent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            TimeComponent::Hour => write!(f, "hour"),
+            TimeComponent::Minute => write!(f, "minute"),
+            TimeComponent::Second => write!(f, "second"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum IntervalleError {
+    /// The input does not follow the grammar
+    Syntax {
+        span: Span,
+        /// What was being parsed when the error occurred
+        label: Option<&'static str>,
+        /// What the parser would have accepted instead
+        expected: Vec<String>,
+    },
+    /// A word that is not one of the keywords
+    UnknownKeyword {
+        span: Span,
+        expected: Vec<&'static str>,
+        /// The accepted word closest to the one given, if any is close enough
+        suggestion: Option<&'static str>,
+    },
+    /// Input left over after a complete timespec
+    TrailingInput {
+        span: Span,
+        /// The accepted forms closest to the whole input
+        closest: Vec<&'static str>,
+    },
+    /// The month is not between 1 and 12
+    InvalidMonth { month: u8, span: Span },
+    /// The day does not exist in its month
+    InvalidDay {
+        day: u8,
+        month: Month,
+        year: i32,
+        span: Span,
+    },
+    /// An hour, minute or second is out of range
+    InvalidTime {
+        component: TimeComponent,
+        value: u8,
+        span: Span,
+    },
+    /// The weekday given does not match the date it precedes
+    WeekdayMismatch {
+        given: Weekday,
+        actual: Weekday,
+        span: Span,
+    },
+    /// The timespec falls outside of the supported range of dates
+    Overflow { span: Span },
+    /// The time span is longer than a `Duration` can hold
+    SpanOverflow { span: Span },
+    /// A field of a calendar event is out of range or empty
+    InvalidCalendarValue { field: &'static str, span: Span },
+    /// The rules of the timezone needed to place a timespec on the timeline could not be used
+    TimeZoneError(Box<dyn Error + Send + Sync>),
+    /// The wall clock time is skipped by a DST transition in its zone
+    NonexistentLocalTime(DateTime),
+    /// The wall clock time is repeated by a DST transition in its zone
+    AmbiguousLocalTime(DateTime),
+    /// The start of an interval falls after its end
+    InvertedInterval {
+        since: OffsetDateTime,
+        until: OffsetDateTime,
+    },
+}
+
+/// Raised from within the parser when recognised input makes no sense
+#[derive(Debug)]
+pub(crate) struct Failure {
+    pub(crate) kind: FailureKind,
+    /// Byte range of the offending text, relative to where the failing parser started
+    pub(crate) range: Range<usize>,
+}
+
+#[derive(Debug)]
+pub(crate) enum FailureKind {
+    InvalidMonth(u8),
+    InvalidDay { day: u8, month: Month, year: i32 },
+    InvalidTime { component: TimeComponent, value: u8 },
+    WeekdayMismatch { given: Weekday, actual: Weekday },
+    Overflow,
+    SpanOverflow,
+    InvalidCalendarValue(&'static str),
+}
+
+impl Failure {
+    pub(crate) fn new(kind: FailureKind, range: Range<usize>) -> Self {
+        Failure { kind, range }
+    }
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{:?}", self.kind)
+    }
+}
+
+impl Error for Failure {}
+
+impl IntervalleError {
+    /// Build an error from the state of the parser for `grammar` when it failed at `offset`
+    /// in `input`
+    pub(crate) fn from_context(
+        input: &str,
+        offset: usize,
+        error: ContextError,
+        grammar: &Grammar,
+    ) -> Self {
+        if let Some(Failure { kind, range }) = error
+            .cause()
+            .and_then(|cause| cause.downcast_ref::<Failure>())
+        {
+            let span = Span::new(input, offset + range.start..offset + range.end, grammar);
+
+            return match *kind {
+                FailureKind::InvalidMonth(month) => Self::InvalidMonth { month, span },
+                FailureKind::InvalidDay { day, month, year } => Self::InvalidDay {
+                    day,
+                    month,
+                    year,
+                    span,
+                },
+                FailureKind::InvalidTime { component, value } => Self::InvalidTime {
+                    component,
+                    value,
+                    span,
+                },
+                FailureKind::WeekdayMismatch { given, actual } => Self::WeekdayMismatch {
+                    given,
+                    actual,
+                    span,
+                },
+                FailureKind::Overflow => Self::Overflow { span },
+                FailureKind::SpanOverflow => Self::SpanOverflow { span },
+                FailureKind::InvalidCalendarValue(field) => {
+                    Self::InvalidCalendarValue { field, span }
+                }
+            };
+        }
+
+        let rest = &input[offset..];
+
+        if rest.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            let word = rest
+                .find(|c: char| !c.is_ascii_alphabetic())
+                .unwrap_or(rest.len());
+
+            return Self::UnknownKeyword {
+                span: Span::new(input, offset..offset + word, grammar),
+                expected: grammar.keywords.to_vec(),
+                suggestion: None,
+            };
+        }
+
+        let token = rest.find(char::is_whitespace).unwrap_or(rest.len());
+
+        Self::Syntax {
+            span: Span::new(input, offset..offset + token, grammar),
+            label: error.context().find_map(|context| match context {
+                StrContext::Label(label) => Some(*label),
+                _ => None,
+            }),
+            expected: error
+                .context()
+                .filter_map(|context| match context {
+                    StrContext::Expected(value) => Some(value.to_string()),
+                    _ => None,
+                })
+                .collect(),
+        }
+    }
+
+    /// The part of the input the error refers to, for errors raised while parsing
+    pub fn span(&self) -> Option<&Span> {
+        match self {
+            IntervalleError::Syntax { span, .. }
+            | IntervalleError::UnknownKeyword { span, .. }
+            | IntervalleError::TrailingInput { span, .. }
+            | IntervalleError::InvalidMonth { span, .. }
+            | IntervalleError::InvalidDay { span, .. }
+            | IntervalleError::InvalidTime { span, .. }
+            | IntervalleError::WeekdayMismatch { span, .. }
+            | IntervalleError::Overflow { span }
+            | IntervalleError::SpanOverflow { span }
+            | IntervalleError::InvalidCalendarValue { span, .. } => Some(span),
+            IntervalleError::TimeZoneError(_)
+            | IntervalleError::NonexistentLocalTime(_)
+            | IntervalleError::AmbiguousLocalTime(_)
+            | IntervalleError::InvertedInterval { .. } => None,
+        }
+    }
+
+    /// A description of the error, without the input it refers to
+    pub(crate) fn message(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            IntervalleError::Syntax {
+                label, expected, ..
+            } => {
+                match label {
+                    Some(label) => write!(f, "invalid {label}")?,
+                    None => write!(f, "unexpected input")?,
+                }
+                if !expected.is_empty() {
+                    write!(f, ", expected {}", expected.join(" or "))?;
+                }
+                Ok(())
+            }
+            IntervalleError::UnknownKeyword {
+                span,
+                suggestion: Some(suggestion),
+                ..
+            } => write!(
+                f,
+                "unknown keyword `{}`, did you mean '{suggestion}'?",
+                span.text()
+            ),
+            IntervalleError::UnknownKeyword {
+                span,
+                expected,
+                suggestion: None,
+            } => write!(
+                f,
+                "unknown keyword `{}`, expected one of {}",
+                span.text(),
+                expected.join(", ")
+            ),
+            IntervalleError::TrailingInput { span, closest } => {
+                write!(f, "unexpected trailing input `{}`", span.text().trim_end())?;
+                if !closest.is_empty() {
+                    write!(f, ", closest accepted forms are `{}`", closest.join("`, `"))?;
+                }
+                Ok(())
+            }
+            IntervalleError::InvalidMonth { month, .. } => {
+                write!(f, "invalid month {month}, expected 01 to 12")
+            }
+            IntervalleError::InvalidDay {
+                day, month, year, ..
+            } => write!(f, "{month} {year} has no day {day}"),
+            IntervalleError::InvalidTime {
+                component, value, ..
+            } => write!(f, "invalid {component} {value}"),
+            IntervalleError::WeekdayMismatch { given, actual, .. } => {
+                write!(f, "date is a {actual}, not a {given}")
+            }
+            IntervalleError::Overflow { .. } => write!(f, "out of the supported range of dates"),
+            IntervalleError::SpanOverflow { .. } => {
+                write!(f, "out of the supported range of time spans")
+            }
+            IntervalleError::InvalidCalendarValue { field, span } => {
+                write!(f, "invalid {field} `{}` in calendar event", span.text())
+            }
+            IntervalleError::TimeZoneError(e) => write!(f, "timezone error: {e}"),
+            IntervalleError::NonexistentLocalTime(dtime) => {
+                write!(f, "{dtime} is skipped by a DST transition")
+            }
+            IntervalleError::AmbiguousLocalTime(dtime) => {
+                write!(f, "{dtime} occurs twice because of a DST transition")
+            }
+            IntervalleError::InvertedInterval { since, until } => {
+                write!(f, "interval starts at {since}, after it ends at {until}")
+            }
+        }
+    }
+}
+
+/// Writes the error's description through `fmt::Display`
+pub(crate) struct Message<'a>(pub(crate) &'a IntervalleError);
+
+impl fmt::Display for Message<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        self.0.message(f)
+    }
+}
+
+impl fmt::Display for IntervalleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self.span() {
+            Some(Span { input, range, .. }) => {
+                let offset = range.start;
+                write!(f, "\n    |\n{offset:3} | {input}\n    | ")?;
+                for _ in 0..offset {
+                    write!(f, " ")?;
+                }
+                write!(f, "^ {}", Message(self))
+            }
+            None => self.message(f),
+        }
+    }
+}
+
+impl Error for IntervalleError {}
+
+#[test]
+fn test_edit_distance() {
+    assert_eq!(edit_distance("", ""), 0);
+    assert_eq!(edit_distance("today", "today"), 0);
+    assert_eq!(edit_distance("tomorow", "tomorrow"), 1);
+    assert_eq!(edit_distance("yesturday", "yesterday"), 1);
+    assert_eq!(edit_distance("kitten", "sitting"), 3);
+    assert_eq!(edit_distance("µs", "us"), 1);
+}
+
+#[test]
+fn test_closest_forms() {
+    assert_eq!(
+        closest_forms("1999-01-01 00:00", &TIMESPEC_FORMS),
+        vec!["2024-08-08 14:10"]
+    );
+    assert_eq!(
+        closest_forms("-3 days ago", &TIMESPEC_FORMS),
+        vec!["+2 days"]
+    );
+}
+
+#[test]
+fn test_misspelling() {
+    for (input, expected) in [
+        ("yesturday", "yesterday"),
+        ("-tomorow", "tomorrow"),
+        ("Fryday 2024-08-08", "friday"),
+        ("5 minuts ago", "minutes"),
+        ("5min agp", "ago"),
+    ] {
+        match misspelling(input, &TIMESPEC) {
+            Some(IntervalleError::UnknownKeyword { suggestion, .. }) => {
+                assert_eq!(suggestion, Some(expected), "{input}")
+            }
+            other => panic!("unexpected result for {input}: {other:?}"),
+        }
+    }
+
+    assert!(misspelling("2024-08-08 14:10:11 UTC", &TIMESPEC).is_none());
+    assert!(misspelling("-10 parsecs", &TIMESPEC).is_none());
+    assert!(misspelling("-1h x", &TIMESPEC).is_none());
+}
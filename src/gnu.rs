@@ -0,0 +1,468 @@
+//! The relative items of GNU `date -d`, e.g. `last monday`, `next week` or `2 days ago 14:00`
+
+use crate::{
+    error::{Failure, FailureKind},
+    parser::{self, validate},
+    Anchor, TimeComponent, TimeSpec, Zone,
+};
+use time::{Date, Duration, Month, PrimitiveDateTime as DateTime, Time, Weekday};
+use winnow::{
+    ascii::{digit1, space0, space1, Caseless},
+    combinator::{alt, cut_err, not, opt, preceded, separated, terminated},
+    error::{ContextError, StrContext, StrContextValue},
+    prelude::*,
+    token::{literal, one_of},
+};
+
+/// A change of date and time, applied in calendar months and days, then elapsed seconds
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+struct Delta {
+    months: i64,
+    days: i64,
+    seconds: i64,
+}
+
+impl Delta {
+    fn checked_mul(self, count: i64) -> Option<Self> {
+        Some(Delta {
+            months: self.months.checked_mul(count)?,
+            days: self.days.checked_mul(count)?,
+            seconds: self.seconds.checked_mul(count)?,
+        })
+    }
+
+    fn checked_add(self, other: Self) -> Option<Self> {
+        Some(Delta {
+            months: self.months.checked_add(other.months)?,
+            days: self.days.checked_add(other.days)?,
+            seconds: self.seconds.checked_add(other.seconds)?,
+        })
+    }
+}
+
+/// One item of a GNU date string
+#[derive(PartialEq, Debug, Clone, Copy)]
+enum Item {
+    Date(Date),
+    Time(Time),
+    /// A weekday, with the number of weeks to move past its next occurrence
+    Weekday(Weekday, i64),
+    Relative(Delta),
+}
+
+/// Add `months` calendar months to `dtime`, days past the end of the month carrying over into
+/// the next one as GNU date does
+fn add_months(dtime: DateTime, months: i64) -> Option<DateTime> {
+    let month = i64::from(dtime.year()) * 12 + i64::from(u8::from(dtime.month())) - 1 + months;
+    let first = Date::from_calendar_date(
+        i32::try_from(month.div_euclid(12)).ok()?,
+        Month::try_from(u8::try_from(month.rem_euclid(12) + 1).ok()?).ok()?,
+        1,
+    )
+    .ok()?;
+
+    first
+        .checked_add(Duration::days(i64::from(dtime.day()) - 1))
+        .map(|date| date.with_time(dtime.time()))
+}
+
+/// The date and time designated by `items`, read against `anchor`, with the zone it is in
+///
+/// A date or weekday without a time of day designates midnight. A weekday moves the date to
+/// its next occurrence, today included, then by as many weeks as its ordinal: `next` skips
+/// today, `last` designates the previous occurrence. Elapsed time alone, as in `2 hours ago`,
+/// moves the anchor the way a systemd time span does.
+fn resolve(items: &[Item], anchor: Anchor) -> Option<(DateTime, Zone)> {
+    let mut date = None;
+    let mut time = None;
+    let mut weekday = None;
+    let mut delta = Delta::default();
+
+    for item in items {
+        match *item {
+            Item::Date(value) => date = Some(value),
+            Item::Time(value) => time = Some(value),
+            Item::Weekday(day, ordinal) => weekday = Some((day, ordinal)),
+            Item::Relative(value) => delta = delta.checked_add(value)?,
+        }
+    }
+
+    if (date, time, weekday, delta.months, delta.days) == (None, None, None, 0, 0) {
+        return anchor.shift(Duration::seconds(delta.seconds));
+    }
+
+    let anchor = anchor.wall();
+    let midnight = date.is_some() || weekday.is_some();
+    let mut dtime = DateTime::new(
+        date.unwrap_or(anchor.date()),
+        time.unwrap_or(if midnight {
+            Time::MIDNIGHT
+        } else {
+            anchor.time()
+        }),
+    );
+
+    if let (Some((day, ordinal)), None) = (weekday, date) {
+        let today = i64::from(dtime.weekday().number_days_from_monday());
+        let target = i64::from(day.number_days_from_monday());
+        let weeks = ordinal - i64::from(ordinal > 0 && today != target);
+
+        dtime = dtime.checked_add(Duration::days((target - today).rem_euclid(7) + 7 * weeks))?;
+    }
+
+    add_months(dtime, delta.months)?
+        .checked_add(Duration::seconds(delta.days.checked_mul(86_400)?))?
+        .checked_add(Duration::seconds(delta.seconds))
+        .map(|dtime| (dtime, Zone::Local))
+}
+
+/// Whether `items` give at most one date, time of day and weekday
+fn unique(items: &[Item]) -> bool {
+    let count = |kind: fn(&Item) -> bool| items.iter().filter(|item| kind(item)).count();
+
+    count(|item| matches!(item, Item::Date(_))) <= 1
+        && count(|item| matches!(item, Item::Time(_))) <= 1
+        && count(|item| matches!(item, Item::Weekday(..))) <= 1
+}
+
+/// The end of a word, so that `mon` is not read from `month`
+fn boundary<'i>() -> impl Parser<&'i str, (), ContextError> {
+    not(one_of(|c: char| c.is_alphanumeric()))
+}
+
+/// `next`, `last` or `this`, moving by one unit forward, backward or not at all
+fn ordinal<'i>() -> impl Parser<&'i str, i64, ContextError> {
+    alt((
+        Caseless("next").value(1),
+        Caseless("last").value(-1),
+        Caseless("this").value(0),
+    ))
+    .context(StrContext::Label("ordinal"))
+}
+
+/// The units of relative items, longest spellings first
+fn unit<'i>() -> impl Parser<&'i str, Delta, ContextError> {
+    let months = |months| Delta {
+        months,
+        ..Delta::default()
+    };
+    let days = |days| Delta {
+        days,
+        ..Delta::default()
+    };
+    let seconds = |seconds| Delta {
+        seconds,
+        ..Delta::default()
+    };
+
+    terminated(
+        alt((
+            alt((Caseless("years"), Caseless("year"))).value(months(12)),
+            alt((Caseless("months"), Caseless("month"))).value(months(1)),
+            alt((Caseless("fortnights"), Caseless("fortnight"))).value(days(14)),
+            alt((Caseless("weeks"), Caseless("week"))).value(days(7)),
+            alt((Caseless("days"), Caseless("day"))).value(days(1)),
+            alt((Caseless("hours"), Caseless("hour"))).value(seconds(3_600)),
+            alt((
+                Caseless("minutes"),
+                Caseless("minute"),
+                Caseless("mins"),
+                Caseless("min"),
+            ))
+            .value(seconds(60)),
+            alt((
+                Caseless("seconds"),
+                Caseless("second"),
+                Caseless("secs"),
+                Caseless("sec"),
+            ))
+            .value(seconds(1)),
+        )),
+        boundary(),
+    )
+    .context(StrContext::Label("unit"))
+}
+
+/// A relative item, e.g. `2 days`, `-3 weeks`, `next month`, `week` or `1 hour ago`
+fn relative<'i>() -> impl Parser<&'i str, Delta, ContextError> {
+    validate(
+        (
+            alt((
+                terminated(
+                    (opt(one_of(['+', '-'])), digit1)
+                        .take()
+                        .try_map(str::parse::<i64>),
+                    space0,
+                ),
+                terminated(ordinal(), space1),
+                "".value(1),
+            )),
+            unit(),
+            opt(preceded(space1, terminated(Caseless("ago"), boundary())).value(-1)),
+        )
+            .with_taken(),
+        |((count, delta, ago), taken): ((i64, Delta, Option<i64>), &str)| {
+            delta
+                .checked_mul(count)
+                .and_then(|delta| delta.checked_mul(ago.unwrap_or(1)))
+                .ok_or_else(|| parser::overflow(taken))
+        },
+    )
+    .context(StrContext::Label("relative item"))
+}
+
+/// `now`, `today`, `yesterday` or `tomorrow`, moving by whole days from the current time
+fn keyword<'i>() -> impl Parser<&'i str, Delta, ContextError> {
+    terminated(
+        alt((
+            alt((Caseless("now"), Caseless("today"))).value(0),
+            Caseless("yesterday").value(-1),
+            Caseless("tomorrow").value(1),
+        )),
+        boundary(),
+    )
+    .map(|days| Delta {
+        days,
+        ..Delta::default()
+    })
+}
+
+/// The hour as written, then minutes, seconds and fraction, then whether the time is `pm`
+type ClockParts<'i> = (
+    (u8, &'i str),
+    Option<(u8, Option<(u8, Option<u32>)>)>,
+    Option<bool>,
+);
+
+/// A time of day on the 24-hour clock or with `am`/`pm`, e.g. `9:30`, `14:00:05` or `2pm`
+fn clock<'i>() -> impl Parser<&'i str, Time, ContextError> {
+    validate(
+        (
+            digit1
+                .verify(|s: &str| s.len() <= 2)
+                .try_map(str::parse::<u8>)
+                .with_taken(),
+            opt(preceded(
+                ":",
+                (
+                    digits!(2, u8),
+                    opt(preceded(
+                        ":",
+                        (digits!(2, u8), opt(preceded(".", fraction!()))),
+                    )),
+                ),
+            )),
+            opt(preceded(
+                space0,
+                terminated(
+                    alt((Caseless("am").value(false), Caseless("pm").value(true))),
+                    boundary(),
+                ),
+            )),
+        )
+            .verify(|(_, minutes, pm)| minutes.is_some() || pm.is_some()),
+        |((hour, digits), minutes, pm): ClockParts| {
+            let (minute, seconds) = minutes.unwrap_or((0, None));
+            let (second, nano) = seconds.unwrap_or((0, None));
+            let invalid = |component, value| {
+                let range = match component {
+                    TimeComponent::Hour => 0..digits.len(),
+                    TimeComponent::Minute => digits.len() + 1..digits.len() + 3,
+                    TimeComponent::Second => digits.len() + 4..digits.len() + 6,
+                };
+
+                Failure::new(FailureKind::InvalidTime { component, value }, range)
+            };
+
+            let hour = match pm {
+                Some(_) if !(1..=12).contains(&hour) => {
+                    return Err(invalid(TimeComponent::Hour, hour))
+                }
+                Some(pm) => hour % 12 + if pm { 12 } else { 0 },
+                None => hour,
+            };
+
+            Time::from_hms_nano(hour, minute, second, nano.unwrap_or(0)).map_err(|_| {
+                match (hour, minute) {
+                    (24.., _) => invalid(TimeComponent::Hour, hour),
+                    (_, 60..) => invalid(TimeComponent::Minute, minute),
+                    _ => invalid(TimeComponent::Second, second),
+                }
+            })
+        },
+    )
+    .context(StrContext::Label("time"))
+}
+
+/// One item, keywords and weekdays being tried before units they could be read as
+fn item<'i>() -> impl Parser<&'i str, Item, ContextError> {
+    alt((
+        keyword().map(Item::Relative),
+        (
+            opt(terminated(ordinal(), space1)),
+            terminated(weekday!(), (boundary(), opt(literal(",")))),
+        )
+            .map(|(ordinal, day)| Item::Weekday(day, ordinal.unwrap_or(0))),
+        relative().map(Item::Relative),
+        date!().map(|dtime: DateTime| Item::Date(dtime.date())),
+        clock().map(Item::Time),
+    ))
+}
+
+/// A date string made of GNU items separated by spaces, resolved against `anchor`
+pub(crate) fn timespec<'i>(anchor: Anchor) -> impl Parser<&'i str, TimeSpec, ContextError> {
+    validate(
+        separated(1.., item(), space1)
+            .verify(|items: &Vec<Item>| unique(items))
+            .with_taken(),
+        move |(items, taken): (Vec<Item>, &str)| {
+            resolve(&items, anchor).ok_or_else(|| parser::overflow(taken))
+        },
+    )
+    .map(|(dtime, zone)| TimeSpec::Point(dtime, zone))
+    .context(StrContext::Label("GNU date string"))
+}
+
+#[test]
+fn test_gnu_relative() {
+    // A Thursday
+    let anchor = time::Date::from_calendar_date(2024, time::Month::August, 8)
+        .unwrap()
+        .with_hms(14, 10, 11)
+        .unwrap();
+    let at = |month, day, hour, minute, second| {
+        time::Date::from_calendar_date(2024, month, day)
+            .unwrap()
+            .with_hms(hour, minute, second)
+            .unwrap()
+    };
+
+    for (timespec, expected) in [
+        ("now", anchor),
+        ("today", anchor),
+        ("yesterday", at(time::Month::August, 7, 14, 10, 11)),
+        ("tomorrow 9am", at(time::Month::August, 9, 9, 0, 0)),
+        ("2 days ago 14:00", at(time::Month::August, 6, 14, 0, 0)),
+        ("1 month ago", at(time::Month::July, 8, 14, 10, 11)),
+        ("next week", at(time::Month::August, 15, 14, 10, 11)),
+        ("last year", anchor.replace_year(2023).unwrap()),
+        ("+3 hours", at(time::Month::August, 8, 17, 10, 11)),
+        ("-1 fortnight", at(time::Month::July, 25, 14, 10, 11)),
+        ("hour ago", at(time::Month::August, 8, 13, 10, 11)),
+        (
+            "3 days 2 hours ago",
+            at(time::Month::August, 11, 12, 10, 11),
+        ),
+        ("12:30pm", at(time::Month::August, 8, 12, 30, 0)),
+        ("12am", at(time::Month::August, 8, 0, 0, 0)),
+        ("2024-01-31 1 month", at(time::Month::March, 2, 0, 0, 0)),
+        ("2024-08-01 9:05", at(time::Month::August, 1, 9, 5, 0)),
+    ] {
+        assert_eq!(
+            TimeSpec::parse_with_dialect(timespec, anchor, crate::Dialect::Gnu).unwrap(),
+            TimeSpec::Point(expected, Zone::Local),
+            "{timespec}"
+        );
+    }
+}
+
+#[test]
+fn test_gnu_weekday() {
+    // A Thursday
+    let anchor = time::Date::from_calendar_date(2024, time::Month::August, 8)
+        .unwrap()
+        .with_hms(14, 10, 11)
+        .unwrap();
+    let day = |day| {
+        time::Date::from_calendar_date(2024, time::Month::August, day)
+            .unwrap()
+            .midnight()
+    };
+
+    for (timespec, expected) in [
+        ("thursday", day(8)),
+        ("Friday", day(9)),
+        ("monday", day(12)),
+        ("next thursday", day(15)),
+        ("next fri", day(9)),
+        ("last thursday", day(1)),
+        ("last monday", day(5)),
+        ("this sunday", day(11)),
+        ("last monday 10:00", day(5).replace_hour(10).unwrap()),
+    ] {
+        assert_eq!(
+            TimeSpec::parse_with_dialect(timespec, anchor, crate::Dialect::Gnu).unwrap(),
+            TimeSpec::Point(expected, Zone::Local),
+            "{timespec}"
+        );
+    }
+}
+
+#[test]
+fn test_gnu_instant() {
+    // 03:30 in Paris, an hour after the clocks went forward
+    let anchor = time::Date::from_calendar_date(2024, time::Month::March, 31)
+        .unwrap()
+        .with_hms(3, 30, 0)
+        .unwrap()
+        .assume_offset(time::UtcOffset::from_hms(2, 0, 0).unwrap());
+    let gnu = |timespec| TimeSpec::parse_with_dialect(timespec, anchor, crate::Dialect::Gnu);
+
+    assert_eq!(
+        gnu("2 hours ago").unwrap(),
+        TimeSpec::Point(
+            time::Date::from_calendar_date(2024, time::Month::March, 30)
+                .unwrap()
+                .with_hms(23, 30, 0)
+                .unwrap(),
+            Zone::Fixed(time::UtcOffset::UTC)
+        )
+    );
+    assert_eq!(
+        gnu("yesterday").unwrap(),
+        TimeSpec::Point(
+            time::Date::from_calendar_date(2024, time::Month::March, 30)
+                .unwrap()
+                .with_hms(3, 30, 0)
+                .unwrap(),
+            Zone::Local
+        )
+    );
+}
+
+#[test]
+fn test_gnu_fallback() {
+    let anchor = time::Date::from_calendar_date(2024, time::Month::August, 8)
+        .unwrap()
+        .with_hms(14, 10, 11)
+        .unwrap();
+    let gnu = |timespec| TimeSpec::parse_with_dialect(timespec, anchor, crate::Dialect::Gnu);
+
+    // systemd forms remain available, with the systemd meaning
+    for timespec in [
+        "-2h",
+        "+2024-08-08 14:10",
+        "2024-08-08T14:10:11Z",
+        "@1723126211",
+    ] {
+        assert_eq!(
+            gnu(timespec).unwrap(),
+            TimeSpec::parse_with_anchor(timespec, anchor).unwrap(),
+            "{timespec}"
+        );
+    }
+
+    // GNU items are not part of the systemd grammar
+    assert!(TimeSpec::parse_with_anchor("last monday", anchor).is_err());
+
+    for timespec in ["next", "13pm", "monday tuesday", "2024-08-08 2024-08-09"] {
+        assert!(gnu(timespec).is_err(), "{timespec}");
+    }
+    assert!(matches!(
+        gnu("2 fortnihgt ago"),
+        Err(crate::IntervalleError::UnknownKeyword {
+            suggestion: Some("fortnight"),
+            ..
+        })
+    ));
+}
@@ -0,0 +1,424 @@
+use crate::{
+    error::RANGE,
+    parser::{self, timespec},
+    Anchor, Clock, IntervalleError, SystemClock, TimeSpec,
+};
+use std::{fmt, str::FromStr};
+use time::{Duration, OffsetDateTime};
+use winnow::{
+    ascii::space1,
+    combinator::{alt, opt, separated_pair},
+    error::StrContext,
+    prelude::*,
+};
+
+/// A span of time between two optional `TimeSpec`s, as given to `--since` and `--until`
+///
+/// Both bounds are inclusive, matching journalctl's "on or newer than" and "on or older than".
+/// A missing bound leaves the interval open on that side.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Interval {
+    since: Option<TimeSpec>,
+    until: Option<TimeSpec>,
+    start: Option<OffsetDateTime>,
+    end: Option<OffsetDateTime>,
+}
+
+impl Interval {
+    /// Build an interval from its bounds, checking that `since` does not come after `until`
+    ///
+    /// Bounds in `Zone::Local` are placed on the timeline using the system's timezone.
+    pub fn new(since: Option<TimeSpec>, until: Option<TimeSpec>) -> Result<Self, IntervalleError> {
+        Interval::new_in(&SystemClock, since, until)
+    }
+
+    /// Build an interval from its bounds, placing those in `Zone::Local` on the timeline using
+    /// the timezone of `clock`
+    pub fn new_in(
+        clock: &impl Clock,
+        since: Option<TimeSpec>,
+        until: Option<TimeSpec>,
+    ) -> Result<Self, IntervalleError> {
+        let start = since.as_ref().map(|s| s.resolve_in(clock)).transpose()?;
+        let end = until.as_ref().map(|s| s.resolve_in(clock)).transpose()?;
+
+        if let (Some(since), Some(until)) = (start, end) {
+            if since > until {
+                return Err(IntervalleError::InvertedInterval { since, until });
+            }
+        }
+
+        Ok(Interval {
+            since,
+            until,
+            start,
+            end,
+        })
+    }
+
+    pub fn parse(since: Option<&str>, until: Option<&str>) -> Result<Self, IntervalleError> {
+        Interval::parse_in(&SystemClock, since, until)
+    }
+
+    /// Parse both bounds against the current time and timezone of `clock`
+    pub fn parse_in(
+        clock: &impl Clock,
+        since: Option<&str>,
+        until: Option<&str>,
+    ) -> Result<Self, IntervalleError> {
+        let anchor = clock.anchor();
+
+        Interval::new_in(
+            clock,
+            since
+                .map(|s| TimeSpec::parse_with_anchor(s, anchor))
+                .transpose()?,
+            until
+                .map(|s| TimeSpec::parse_with_anchor(s, anchor))
+                .transpose()?,
+        )
+    }
+
+    /// Parse both bounds against the same `anchor`
+    ///
+    /// Bounds in `Zone::Local` are placed on the timeline at the offset of `anchor`, or in UTC
+    /// when it is a wall clock time.
+    pub fn parse_with_anchor(
+        since: Option<&str>,
+        until: Option<&str>,
+        anchor: impl Into<Anchor>,
+    ) -> Result<Self, IntervalleError> {
+        let anchor = anchor.into();
+
+        Interval::new_in(
+            &anchor.clock()?,
+            since
+                .map(|s| TimeSpec::parse_with_anchor(s, anchor))
+                .transpose()?,
+            until
+                .map(|s| TimeSpec::parse_with_anchor(s, anchor))
+                .transpose()?,
+        )
+    }
+
+    pub fn parse_range(range: &str) -> Result<Self, IntervalleError> {
+        Interval::parse_range_in(&SystemClock, range)
+    }
+
+    /// Parse an interval given as a single string against the current time and timezone of
+    /// `clock`
+    pub fn parse_range_in(clock: &impl Clock, range: &str) -> Result<Self, IntervalleError> {
+        let (since, until) = Interval::parse_bounds(range, clock.anchor().into())?;
+
+        Interval::new_in(clock, since, until)
+    }
+
+    /// Parse an interval given as a single string, either as `<since>..<until>` where both
+    /// ends are optional, or as `<since> to <until>`
+    ///
+    /// As with `TimeSpec::parse_with_anchor`, the whole of `range` must be consumed. Bounds in
+    /// `Zone::Local` are placed on the timeline as by `Interval::parse_with_anchor`.
+    pub fn parse_range_with_anchor(
+        range: &str,
+        anchor: impl Into<Anchor>,
+    ) -> Result<Self, IntervalleError> {
+        let anchor = anchor.into();
+        let (since, until) = Interval::parse_bounds(range, anchor)?;
+
+        Interval::new_in(&anchor.clock()?, since, until)
+    }
+
+    /// The bounds of an interval given as a single string
+    fn parse_bounds(
+        range: &str,
+        anchor: Anchor,
+    ) -> Result<(Option<TimeSpec>, Option<TimeSpec>), IntervalleError> {
+        parser::complete(
+            alt((
+                separated_pair(opt(timespec(anchor)), "..", opt(timespec(anchor))),
+                separated_pair(timespec(anchor), (space1, "to", space1), timespec(anchor))
+                    .map(|(since, until)| (Some(since), Some(until))),
+            ))
+            .context(StrContext::Label("range")),
+            range,
+            &RANGE,
+        )
+    }
+
+    pub fn since(&self) -> Option<&TimeSpec> {
+        self.since.as_ref()
+    }
+
+    pub fn until(&self) -> Option<&TimeSpec> {
+        self.until.as_ref()
+    }
+
+    /// The first instant of the interval, if it is bounded in the past
+    pub fn start(&self) -> Option<OffsetDateTime> {
+        self.start
+    }
+
+    /// The last instant of the interval, if it is bounded in the future
+    pub fn end(&self) -> Option<OffsetDateTime> {
+        self.end
+    }
+
+    /// Whether `instant` falls within the interval, bounds included
+    pub fn contains(&self, instant: OffsetDateTime) -> bool {
+        self.start.is_none_or(|start| start <= instant) && self.end.is_none_or(|end| instant <= end)
+    }
+
+    /// The length of the interval, or `None` if it is open on either side
+    pub fn duration(&self) -> Option<Duration> {
+        Some(self.end? - self.start?)
+    }
+}
+
+/// Parses a range as `Interval::parse_range` does
+impl FromStr for Interval {
+    type Err = IntervalleError;
+
+    fn from_str(range: &str) -> Result<Self, Self::Err> {
+        Interval::parse_range(range)
+    }
+}
+
+/// Writes the interval as a range that `Interval::parse_range` reads back, with both bounds in
+/// their canonical form, e.g. `2024-08-08 10:00:00 UTC..` for an interval open in the future
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        if let Some(since) = &self.since {
+            write!(f, "{since}")?;
+        }
+        write!(f, "..")?;
+        if let Some(until) = &self.until {
+            write!(f, "{until}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_interval() {
+    let anchor = time::Date::from_calendar_date(2024, time::Month::August, 8)
+        .unwrap()
+        .with_time(time::Time::from_hms(12, 0, 0).unwrap());
+
+    let interval = Interval::parse_with_anchor(
+        Some("2024-08-08 10:00 UTC"),
+        Some("2024-08-08 12:00 UTC"),
+        anchor,
+    )
+    .unwrap();
+
+    let start = anchor.assume_utc() - Duration::hours(2);
+    let end = anchor.assume_utc();
+
+    assert_eq!(interval.start(), Some(start));
+    assert_eq!(interval.end(), Some(end));
+    assert_eq!(interval.duration(), Some(Duration::hours(2)));
+
+    assert!(interval.contains(start));
+    assert!(interval.contains(end));
+    assert!(interval.contains(start + Duration::hours(1)));
+    assert!(interval.contains(
+        (start + Duration::hours(1)).to_offset(time::UtcOffset::from_hms(2, 0, 0).unwrap())
+    ));
+    assert!(!interval.contains(start - Duration::nanoseconds(1)));
+    assert!(!interval.contains(end + Duration::nanoseconds(1)));
+}
+
+#[test]
+fn test_interval_open() {
+    let anchor = time::Date::from_calendar_date(2024, time::Month::August, 8)
+        .unwrap()
+        .with_time(time::Time::from_hms(12, 0, 0).unwrap());
+
+    let interval = Interval::parse_with_anchor(Some("2024-08-08 UTC"), None, anchor).unwrap();
+
+    assert_eq!(
+        interval.since(),
+        Some(&TimeSpec::Point(
+            anchor.date().midnight(),
+            crate::Zone::Fixed(time::UtcOffset::UTC)
+        ))
+    );
+    assert_eq!(interval.until(), None);
+    assert_eq!(interval.duration(), None);
+    assert!(interval.contains(anchor.assume_utc() + Duration::weeks(1000)));
+    assert!(!interval.contains(anchor.assume_utc() - Duration::days(1)));
+
+    let interval = Interval::parse_with_anchor(None, None, anchor).unwrap();
+    assert!(interval.contains(anchor.assume_utc()));
+}
+
+#[test]
+fn test_interval_inverted() {
+    let anchor = time::Date::from_calendar_date(2024, time::Month::August, 8)
+        .unwrap()
+        .with_time(time::Time::from_hms(12, 0, 0).unwrap());
+
+    let error = Interval::parse_with_anchor(
+        Some("2024-08-08 12:00 +02:00"),
+        Some("2024-08-08 09:00 UTC"),
+        anchor,
+    )
+    .unwrap_err();
+
+    assert!(matches!(error, IntervalleError::InvertedInterval { .. }));
+
+    assert!(Interval::parse_with_anchor(
+        Some("2024-08-08 11:00 +02:00"),
+        Some("2024-08-08 09:00 UTC"),
+        anchor,
+    )
+    .is_ok());
+}
+
+#[test]
+fn test_range() {
+    let anchor = time::Date::from_calendar_date(2024, time::Month::August, 8)
+        .unwrap()
+        .with_time(time::Time::from_hms(12, 0, 0).unwrap());
+
+    for (range, since, until) in [
+        ("yesterday..today", Some("yesterday"), Some("today")),
+        (
+            "2024-08-01..2024-08-08",
+            Some("2024-08-01"),
+            Some("2024-08-08"),
+        ),
+        ("10:00 to 11:30", Some("10:00"), Some("11:30")),
+        (
+            "2024-08-01 to 2024-08-08 UTC",
+            Some("2024-08-01"),
+            Some("2024-08-08 UTC"),
+        ),
+        ("12:00:00.5..12:00:01", Some("12:00:00.5"), Some("12:00:01")),
+        ("-2h..", Some("-2h"), None),
+        ("..yesterday", None, Some("yesterday")),
+        ("2 days ago..1h ago", Some("-2d"), Some("-1h")),
+    ] {
+        assert_eq!(
+            Interval::parse_range_with_anchor(range, anchor).unwrap(),
+            Interval::parse_with_anchor(since, until, anchor).unwrap(),
+            "{range}"
+        )
+    }
+}
+
+#[test]
+fn test_range_invalid() {
+    let anchor = time::Date::from_calendar_date(2024, time::Month::August, 8)
+        .unwrap()
+        .with_time(time::Time::from_hms(12, 0, 0).unwrap());
+
+    assert!(matches!(
+        Interval::parse_range_with_anchor("today..yesterday", anchor),
+        Err(IntervalleError::InvertedInterval { .. })
+    ));
+
+    for range in [
+        "today...tomorrow",
+        "today..tomorow",
+        "today to",
+        "today-tomorrow",
+    ] {
+        assert!(
+            Interval::parse_range_with_anchor(range, anchor).is_err_and(|e| e.span().is_some()),
+            "{range}"
+        )
+    }
+}
+
+#[test]
+fn test_range_trailing() {
+    let anchor = time::Date::from_calendar_date(2024, time::Month::August, 8)
+        .unwrap()
+        .with_time(time::Time::from_hms(12, 0, 0).unwrap());
+
+    match Interval::parse_range_with_anchor("10:00 to 11:30 tomorrow", anchor) {
+        Err(IntervalleError::TrailingInput { span, closest }) => {
+            assert_eq!(span.text(), "tomorrow");
+            assert!(closest.contains(&"10:00 to 11:30"));
+        }
+        other => panic!("unexpected result: {other:?}"),
+    }
+}
+
+#[test]
+fn test_interval_clock() {
+    let paris = tz::TimeZone::from_posix_tz("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+    let now = time::Date::from_calendar_date(2024, time::Month::August, 8)
+        .unwrap()
+        .with_hms(12, 0, 0)
+        .unwrap()
+        .assume_utc();
+    let clock = crate::FixedClock::new(now, paris);
+
+    let interval = Interval::parse_range_in(&clock, "today..now").unwrap();
+    assert_eq!(
+        interval.start(),
+        Some(now.replace_hour(22).unwrap() - Duration::days(1))
+    );
+    assert_eq!(interval.end(), Some(now));
+
+    assert_eq!(
+        Interval::parse_in(&clock, Some("today"), Some("now")).unwrap(),
+        interval
+    );
+}
+
+#[test]
+fn test_interval_anchor_zone() {
+    let anchor = time::Date::from_calendar_date(2024, time::Month::August, 8)
+        .unwrap()
+        .with_hms(12, 0, 0)
+        .unwrap();
+    let plus_two = time::UtcOffset::from_hms(2, 0, 0).unwrap();
+
+    let interval =
+        Interval::parse_range_with_anchor("10:00..now", anchor.assume_offset(plus_two)).unwrap();
+    assert_eq!(
+        interval.start(),
+        Some(anchor.replace_hour(10).unwrap().assume_offset(plus_two))
+    );
+    assert_eq!(interval.end(), Some(anchor.assume_offset(plus_two)));
+
+    let interval = Interval::parse_with_anchor(Some("10:00"), None, anchor).unwrap();
+    assert_eq!(
+        interval.start(),
+        Some(anchor.replace_hour(10).unwrap().assume_utc())
+    );
+}
+
+#[test]
+fn test_interval_display() {
+    let anchor = time::Date::from_calendar_date(2024, time::Month::August, 8)
+        .unwrap()
+        .with_time(time::Time::from_hms(12, 0, 0).unwrap());
+
+    for (range, canonical) in [
+        (
+            "2024-08-07 UTC..2024-08-08 UTC",
+            "2024-08-07 00:00:00 UTC..2024-08-08 00:00:00 UTC",
+        ),
+        (
+            "-2024-08-08 10:00 +02:00..",
+            "-2024-08-08 10:00:00 +02:00..",
+        ),
+        (
+            "..2024-08-08 Europe/Paris",
+            "..2024-08-08 00:00:00 Europe/Paris",
+        ),
+        ("..", ".."),
+    ] {
+        let interval = Interval::parse_range_with_anchor(range, anchor).unwrap();
+        assert_eq!(interval.to_string(), canonical, "{range}");
+        assert_eq!(
+            Interval::parse_range_with_anchor(canonical, anchor).unwrap(),
+            interval
+        );
+    }
+}
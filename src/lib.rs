@@ -1,173 +1,213 @@
-use std::error::Error;
-use time::{ext::NumericalDuration, OffsetDateTime, PrimitiveDateTime as DateTime, UtcOffset};
-use winnow::{
-    ascii::digit1,
-    combinator::{alt, cut_err, opt, preceded, separated_pair},
-    error::{ContextError, ParseError, StrContext, StrContextValue},
-    prelude::*,
-    token::literal,
-};
-
-#[derive(Debug)]
-pub enum IntervalleError {
-    ParseError(String, String, usize),
-}
-
-impl<C> From<ParseError<&str, C>> for IntervalleError
-where
-    C: std::fmt::Display,
-{
-    fn from(ce: ParseError<&str, C>) -> Self {
-        Self::ParseError(
-            format!("{}", ce.inner()).replace("\n", ", "),
-            String::from(*ce.input()),
-            ce.offset(),
-        )
-    }
-}
+use std::{fmt, str::FromStr, time::SystemTime};
+use time::{OffsetDateTime, PrimitiveDateTime as DateTime};
+use winnow::combinator::{alt, eof, terminated};
 
-impl std::fmt::Display for IntervalleError {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
-        match self {
-            IntervalleError::ParseError(info, input, offset) => {
-                write!(f, "\n    |\n{offset:3} | {input}\n    | ")?;
-                for _ in 0..*offset {
-                    write!(f, " ")?;
-                }
-                write!(f, "^ {info}")
-            }
-        }
-    }
-}
+#[macro_use]
+mod parser;
+mod calendar;
+#[cfg(feature = "clap")]
+mod cli;
+mod clock;
+mod error;
+mod gnu;
+mod interval;
+#[cfg(feature = "diagnostics")]
+mod report;
+#[cfg(feature = "serde")]
+pub mod serde;
+mod span;
+mod zone;
+
+pub use calendar::{CalendarEvent, Elapses};
+#[cfg(feature = "clap")]
+pub use cli::{IntervalArgs, TimeSpecValueParser};
+pub use clock::{Anchor, Clock, FixedClock, SystemClock};
+pub use error::{IntervalleError, Span, TimeComponent};
+pub use interval::Interval;
+#[cfg(feature = "diagnostics")]
+pub use report::Report;
+pub use span::TimeSpan;
+pub use zone::{Disambiguation, Resolution, Zone};
 
-impl Error for IntervalleError {}
+/// The grammar timespecs are written in
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum Dialect {
+    /// The grammar of systemd.time(7), as taken by journalctl
+    #[default]
+    Systemd,
+    /// The date strings of GNU `date -d`, such as `last monday`, `next week`, `2 days ago 14:00`
+    /// or `1 month ago`, falling back to the systemd grammar
+    ///
+    /// The keywords take their GNU meaning: `yesterday` is the current time of day on the day
+    /// before, and `today` the current time. Months and years are calendar units.
+    Gnu,
+}
 
 #[derive(PartialEq, Debug, Clone)]
 pub enum TimeSpec {
-    After(DateTime),
-    Before(DateTime),
-    Point(DateTime),
+    After(DateTime, Zone),
+    Before(DateTime, Zone),
+    Point(DateTime, Zone),
 }
 
-fn yesterday(anchor: DateTime) -> DateTime {
-    anchor
-        .date()
-        .midnight()
-        .checked_sub(1.days())
-        .expect("Unreacheable, we allow 4 digit years and the library supports i32")
-}
+impl TimeSpec {
+    /// The date and time, as written in the timespec's zone
+    pub fn datetime(&self) -> DateTime {
+        match self {
+            TimeSpec::After(dtime, _) | TimeSpec::Before(dtime, _) | TimeSpec::Point(dtime, _) => {
+                *dtime
+            }
+        }
+    }
 
-fn tomorrow(anchor: DateTime) -> DateTime {
-    anchor
-        .date()
-        .midnight()
-        .checked_add(1.days())
-        .expect("Unreacheable, we allow 4 digit years and the library supports i32")
-}
+    /// The zone the timespec was expressed in
+    pub fn zone(&self) -> &Zone {
+        match self {
+            TimeSpec::After(_, zone) | TimeSpec::Before(_, zone) | TimeSpec::Point(_, zone) => zone,
+        }
+    }
 
-macro_rules! digits {
-    ($len:expr, $dest:ty) => {
-        digit1
-            .verify(|s: &str| s.len() == $len)
-            .try_map(str::parse::<$dest>)
-            .context(StrContext::Label("digit count"))
-    };
-}
+    /// Find where the timespec falls on the timeline
+    ///
+    /// The offset applied is the one its zone observes on the timespec's date, with `local`
+    /// used for timespecs in `Zone::Local`. Times skipped or repeated by a DST transition are
+    /// reported as such.
+    pub fn locate(&self, local: &tz::TimeZone) -> Result<Resolution, IntervalleError> {
+        self.zone().locate(self.datetime(), local)
+    }
 
-macro_rules! date {
-    () => {
-        (
-            digits!(4, u16),
-            preceded(
-                cut_err("-")
-                    .context(StrContext::Label("date delimiter"))
-                    .context(StrContext::Expected(StrContextValue::CharLiteral('-'))),
-                digits!(2, u8),
-            ),
-            preceded(
-                cut_err("-")
-                    .context(StrContext::Label("date delimiter"))
-                    .context(StrContext::Expected(StrContextValue::CharLiteral('-'))),
-                digits!(2, u8),
-            ),
-        )
-            .try_map(|(year, month, day)| {
-                time::Date::from_calendar_date(year as i32, time::Month::try_from(month)?, day)
-            })
-            .map(|d| d.midnight())
-            .context(StrContext::Label("date format"))
-    };
-}
+    /// Place the timespec on the timeline, picking the earliest instant for times skipped or
+    /// repeated by a DST transition
+    pub fn resolve(&self, local: &tz::TimeZone) -> Result<OffsetDateTime, IntervalleError> {
+        self.resolve_with(local, Disambiguation::default())
+    }
 
-macro_rules! time {
-    () => {
-        (
-            digits!(2, u8),
-            preceded(
-                cut_err(":")
-                    .context(StrContext::Label("time delimiter"))
-                    .context(StrContext::Expected(StrContextValue::CharLiteral(':'))),
-                cut_err(digits!(2, u8)),
-            ),
-            opt(preceded(
-                literal(":")
-                    .context(StrContext::Label("time delimiter"))
-                    .context(StrContext::Expected(StrContextValue::CharLiteral(':'))),
-                cut_err(digits!(2, u8)),
-            )),
-        )
-            .try_map(|(hour, min, sec)| time::Time::from_hms(hour, min, sec.unwrap_or(0)))
-    };
-}
+    /// Place the timespec on the timeline, handling DST transitions according to `policy`
+    pub fn resolve_with(
+        &self,
+        local: &tz::TimeZone,
+        policy: Disambiguation,
+    ) -> Result<OffsetDateTime, IntervalleError> {
+        match (self.locate(local)?, policy) {
+            (Resolution::Single(instant), _) => Ok(instant),
+            (Resolution::Ambiguous(..), Disambiguation::Reject) => {
+                Err(IntervalleError::AmbiguousLocalTime(self.datetime()))
+            }
+            (Resolution::Nonexistent(..), Disambiguation::Reject) => {
+                Err(IntervalleError::NonexistentLocalTime(self.datetime()))
+            }
+            (resolution, Disambiguation::Earliest) => Ok(resolution.earliest()),
+            (resolution, Disambiguation::Latest) => Ok(resolution.latest()),
+        }
+    }
 
-impl TimeSpec {
-    /// Figuring out the system's local timezone
-    fn local_offset() -> Result<UtcOffset, Box<dyn Error>> {
-        let time_zone_local = tz::TimeZone::local()?
-            .find_current_local_time_type()?
-            .ut_offset();
+    /// Place the timespec on the timeline, using the system's timezone for `Zone::Local`
+    pub fn resolve_local(&self) -> Result<OffsetDateTime, IntervalleError> {
+        self.resolve_in(&SystemClock)
+    }
+
+    /// Place the timespec on the timeline, using the timezone of `clock` for `Zone::Local`
+    pub fn resolve_in(&self, clock: &impl Clock) -> Result<OffsetDateTime, IntervalleError> {
+        self.resolve(&clock.local()?)
+    }
 
-        UtcOffset::from_whole_seconds(time_zone_local).map_err(|e| e.into())
+    /// The timespec as a `SystemTime`, with `local` used for timespecs in `Zone::Local`
+    pub fn to_system_time(&self, local: &tz::TimeZone) -> Result<SystemTime, IntervalleError> {
+        self.resolve(local).map(SystemTime::from)
     }
 
+    /// Parse `timespec`, resolving relative forms against the system's clock and timezone
     pub fn parse(timespec: &str) -> Result<TimeSpec, IntervalleError> {
-        let now =
-            OffsetDateTime::now_utc().to_offset(Self::local_offset().unwrap_or(UtcOffset::UTC));
+        TimeSpec::parse_in(&SystemClock, timespec)
+    }
 
-        TimeSpec::parse_with_anchor(timespec, DateTime::new(now.date(), now.time()))
+    /// Parse `timespec`, resolving relative forms against the current time of `clock`
+    pub fn parse_in(clock: &impl Clock, timespec: &str) -> Result<TimeSpec, IntervalleError> {
+        TimeSpec::parse_with_anchor(timespec, clock.anchor())
     }
 
+    /// Parse `timespec`, resolving relative forms against `anchor`, either a
+    /// `PrimitiveDateTime` on the local wall clock or an `OffsetDateTime`
+    ///
+    /// The whole of `timespec` must be consumed: anything left after a complete timespec,
+    /// trailing whitespace included, is rejected with `IntervalleError::TrailingInput`.
     pub fn parse_with_anchor(
         timespec: &str,
-        anchor: DateTime,
+        anchor: impl Into<Anchor>,
     ) -> Result<TimeSpec, IntervalleError> {
-        let out: Result<Self, ParseError<&str, ContextError>> = (
-            opt(alt(("+", "-"))),
-            alt((
-                literal("today").value(anchor.date().midnight()),
-                literal("yesterday").value(yesterday(anchor)),
-                literal("tomorrow").value(tomorrow(anchor)),
-                separated_pair(
-                    date!(),
-                    literal(" ").context(StrContext::Expected(StrContextValue::CharLiteral(' '))),
-                    cut_err(time!()).context(StrContext::Label("time")),
-                )
-                .map(|(pdate, ptime)| pdate.replace_time(ptime))
-                .context(StrContext::Label("time_and_date")),
-                date!(),
-                time!().map(|ptime| anchor.replace_time(ptime)),
-            )),
-        )
-            .context(StrContext::Label("timespec"))
-            .map(|(modifier, dtime)| match modifier {
-                Some("+") => Self::After(dtime),
-                Some("-") => Self::Before(dtime),
-                None => Self::Point(dtime),
-                _ => unreachable!(),
-            })
-            .parse(timespec);
+        parser::complete(parser::timespec(anchor.into()), timespec, &error::TIMESPEC)
+    }
+
+    /// Parse `timespec` in the grammar of `dialect`, resolving relative forms against `anchor`
+    pub fn parse_with_dialect(
+        timespec: &str,
+        anchor: impl Into<Anchor>,
+        dialect: Dialect,
+    ) -> Result<TimeSpec, IntervalleError> {
+        let anchor = anchor.into();
 
-        out.map_err(IntervalleError::from)
+        match dialect {
+            Dialect::Systemd => TimeSpec::parse_with_anchor(timespec, anchor),
+            Dialect::Gnu => parser::complete(
+                alt((
+                    terminated(gnu::timespec(anchor), eof),
+                    parser::timespec(anchor),
+                )),
+                timespec,
+                &error::GNU,
+            ),
+        }
+    }
+}
+
+impl FromStr for TimeSpec {
+    type Err = IntervalleError;
+
+    fn from_str(timespec: &str) -> Result<Self, Self::Err> {
+        TimeSpec::parse(timespec)
+    }
+}
+
+impl TryFrom<&str> for TimeSpec {
+    type Error = IntervalleError;
+
+    fn try_from(timespec: &str) -> Result<Self, Self::Error> {
+        TimeSpec::parse(timespec)
+    }
+}
+
+/// Writes the timespec in a canonical form that parses back to it, e.g.
+/// `+2024-08-08 14:10:00 UTC`
+///
+/// Relative forms are written as the date and time they resolved to, `-yesterday` becoming
+/// `-2024-08-07 00:00:00`. Only dates within years 0 to 9999 can be parsed back.
+impl fmt::Display for TimeSpec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let dtime = self.datetime();
+
+        match self {
+            TimeSpec::After(..) => write!(f, "+")?,
+            TimeSpec::Before(..) => write!(f, "-")?,
+            TimeSpec::Point(..) => (),
+        }
+
+        write!(
+            f,
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+            dtime.year(),
+            u8::from(dtime.month()),
+            dtime.day(),
+            dtime.hour(),
+            dtime.minute(),
+            dtime.second()
+        )?;
+
+        if dtime.nanosecond() > 0 {
+            let fraction = format!("{:09}", dtime.nanosecond());
+            write!(f, ".{}", fraction.trim_end_matches('0'))?;
+        }
+
+        self.zone().write_suffix(f)
     }
 }
 
@@ -181,7 +221,33 @@ fn test_today() {
 
     let parsed = TimeSpec::parse_with_anchor("today", anchor).unwrap();
 
-    assert_eq!(parsed, TimeSpec::Point(target))
+    assert_eq!(parsed, TimeSpec::Point(target, Zone::Local))
+}
+
+#[test]
+fn test_now() {
+    let anchor = time::Date::from_calendar_date(2023, time::Month::November, 11)
+        .unwrap()
+        .midnight()
+        .replace_time(time::Time::from_hms(12, 20, 45).unwrap());
+
+    let parsed = TimeSpec::parse_with_anchor("now", anchor).unwrap();
+
+    assert_eq!(parsed, TimeSpec::Point(anchor, Zone::Local))
+}
+
+#[test]
+fn test_before_after_now() {
+    let anchor = time::Date::from_calendar_date(2023, time::Month::November, 11)
+        .unwrap()
+        .midnight()
+        .replace_time(time::Time::from_hms(12, 20, 45).unwrap());
+
+    let parsed = TimeSpec::parse_with_anchor("-now", anchor).unwrap();
+    assert_eq!(parsed, TimeSpec::Before(anchor, Zone::Local));
+
+    let parsed = TimeSpec::parse_with_anchor("+now", anchor).unwrap();
+    assert_eq!(parsed, TimeSpec::After(anchor, Zone::Local))
 }
 
 #[test]
@@ -197,7 +263,7 @@ fn test_yesterday() {
 
     let parsed = TimeSpec::parse_with_anchor("yesterday", anchor).unwrap();
 
-    assert_eq!(parsed, TimeSpec::Point(target))
+    assert_eq!(parsed, TimeSpec::Point(target, Zone::Local))
 }
 
 #[test]
@@ -213,12 +279,12 @@ fn test_tomorrow() {
 
     let parsed = TimeSpec::parse_with_anchor("tomorrow", anchor).unwrap();
 
-    assert_eq!(parsed, TimeSpec::Point(target))
+    assert_eq!(parsed, TimeSpec::Point(target, Zone::Local))
 }
 
 #[test]
 fn test_date_time() {
-    let target = time::Date::from_calendar_date(2024, time::Month::August, 08)
+    let target = time::Date::from_calendar_date(2024, time::Month::August, 8)
         .unwrap()
         .midnight()
         .replace_time(time::Time::from_hms(14, 10, 11).unwrap());
@@ -230,12 +296,12 @@ fn test_date_time() {
 
     let parsed = TimeSpec::parse_with_anchor("2024-08-08 14:10:11", anchor).unwrap();
 
-    assert_eq!(parsed, TimeSpec::Point(target))
+    assert_eq!(parsed, TimeSpec::Point(target, Zone::Local))
 }
 
 #[test]
 fn test_date_time_no_sec() {
-    let target = time::Date::from_calendar_date(2024, time::Month::August, 08)
+    let target = time::Date::from_calendar_date(2024, time::Month::August, 8)
         .unwrap()
         .midnight()
         .replace_time(time::Time::from_hms(14, 10, 00).unwrap());
@@ -247,12 +313,12 @@ fn test_date_time_no_sec() {
 
     let parsed = TimeSpec::parse_with_anchor("2024-08-08 14:10", anchor).unwrap();
 
-    assert_eq!(parsed, TimeSpec::Point(target))
+    assert_eq!(parsed, TimeSpec::Point(target, Zone::Local))
 }
 
 #[test]
 fn test_date() {
-    let target = time::Date::from_calendar_date(2024, time::Month::August, 08)
+    let target = time::Date::from_calendar_date(2024, time::Month::August, 8)
         .unwrap()
         .midnight();
 
@@ -263,12 +329,12 @@ fn test_date() {
 
     let parsed = TimeSpec::parse_with_anchor("2024-08-08", anchor).unwrap();
 
-    assert_eq!(parsed, TimeSpec::Point(target))
+    assert_eq!(parsed, TimeSpec::Point(target, Zone::Local))
 }
 
 #[test]
 fn test_time() {
-    let target = time::Date::from_calendar_date(2024, time::Month::August, 08)
+    let target = time::Date::from_calendar_date(2024, time::Month::August, 8)
         .unwrap()
         .midnight()
         .replace_time(time::Time::from_hms(15, 27, 59).unwrap());
@@ -277,12 +343,12 @@ fn test_time() {
 
     let parsed = TimeSpec::parse_with_anchor("15:27:59", anchor).unwrap();
 
-    assert_eq!(parsed, TimeSpec::Point(target))
+    assert_eq!(parsed, TimeSpec::Point(target, Zone::Local))
 }
 
 #[test]
 fn test_time_no_sec() {
-    let target = time::Date::from_calendar_date(2024, time::Month::August, 08)
+    let target = time::Date::from_calendar_date(2024, time::Month::August, 8)
         .unwrap()
         .midnight()
         .replace_time(time::Time::from_hms(15, 28, 00).unwrap());
@@ -291,12 +357,12 @@ fn test_time_no_sec() {
 
     let parsed = TimeSpec::parse_with_anchor("15:28", anchor).unwrap();
 
-    assert_eq!(parsed, TimeSpec::Point(target))
+    assert_eq!(parsed, TimeSpec::Point(target, Zone::Local))
 }
 
 #[test]
 fn test_before_time_no_sec() {
-    let target = time::Date::from_calendar_date(2024, time::Month::August, 08)
+    let target = time::Date::from_calendar_date(2024, time::Month::August, 8)
         .unwrap()
         .midnight()
         .replace_time(time::Time::from_hms(15, 28, 00).unwrap());
@@ -305,12 +371,12 @@ fn test_before_time_no_sec() {
 
     let parsed = TimeSpec::parse_with_anchor("-15:28", anchor).unwrap();
 
-    assert_eq!(parsed, TimeSpec::Before(target))
+    assert_eq!(parsed, TimeSpec::Before(target, Zone::Local))
 }
 
 #[test]
 fn test_after_time_no_sec() {
-    let target = time::Date::from_calendar_date(2024, time::Month::August, 08)
+    let target = time::Date::from_calendar_date(2024, time::Month::August, 8)
         .unwrap()
         .midnight()
         .replace_time(time::Time::from_hms(15, 28, 00).unwrap());
@@ -319,5 +385,814 @@ fn test_after_time_no_sec() {
 
     let parsed = TimeSpec::parse_with_anchor("+15:28", anchor).unwrap();
 
-    assert_eq!(parsed, TimeSpec::After(target))
+    assert_eq!(parsed, TimeSpec::After(target, Zone::Local))
+}
+
+#[test]
+fn test_relative_before() {
+    let anchor = time::Date::from_calendar_date(2024, time::Month::August, 8)
+        .unwrap()
+        .midnight()
+        .replace_time(time::Time::from_hms(12, 20, 45).unwrap());
+
+    let target = anchor.replace_time(time::Time::from_hms(10, 50, 45).unwrap());
+
+    let parsed = TimeSpec::parse_with_anchor("-1h 30min", anchor).unwrap();
+
+    assert_eq!(parsed, TimeSpec::Point(target, Zone::Local))
+}
+
+#[test]
+fn test_relative_after() {
+    let anchor = time::Date::from_calendar_date(2024, time::Month::August, 8)
+        .unwrap()
+        .midnight()
+        .replace_time(time::Time::from_hms(12, 20, 45).unwrap());
+
+    let target = time::Date::from_calendar_date(2024, time::Month::August, 10)
+        .unwrap()
+        .midnight()
+        .replace_time(time::Time::from_hms(12, 20, 45).unwrap());
+
+    let parsed = TimeSpec::parse_with_anchor("+2 days", anchor).unwrap();
+
+    assert_eq!(parsed, TimeSpec::Point(target, Zone::Local))
+}
+
+#[test]
+fn test_relative_instant() {
+    let anchor = time::Date::from_calendar_date(2024, time::Month::August, 8)
+        .unwrap()
+        .with_hms(12, 20, 45)
+        .unwrap()
+        .assume_offset(time::UtcOffset::from_hms(2, 0, 0).unwrap());
+    let utc = |hour| {
+        time::Date::from_calendar_date(2024, time::Month::August, 8)
+            .unwrap()
+            .with_hms(hour, 20, 45)
+            .unwrap()
+    };
+
+    for (input, expected) in [("now", utc(10)), ("-2h", utc(8)), ("1h left", utc(11))] {
+        assert_eq!(
+            TimeSpec::parse_with_anchor(input, anchor).unwrap(),
+            TimeSpec::Point(expected, Zone::Fixed(time::UtcOffset::UTC)),
+            "{input}"
+        );
+    }
+
+    // Dates and times of day are read on the wall clock of the anchor
+    assert_eq!(
+        TimeSpec::parse_with_anchor("today", anchor).unwrap(),
+        TimeSpec::Point(utc(0).date().midnight(), Zone::Local)
+    );
+    assert_eq!(
+        TimeSpec::parse_with_anchor("-15:28", anchor).unwrap(),
+        TimeSpec::Before(
+            utc(0).replace_time(time::Time::from_hms(15, 28, 0).unwrap()),
+            Zone::Local
+        )
+    );
+}
+
+#[test]
+fn test_relative_units() {
+    let anchor = time::Date::from_calendar_date(2024, time::Month::August, 8)
+        .unwrap()
+        .midnight()
+        .replace_time(time::Time::from_hms(12, 20, 45).unwrap());
+
+    for (input, offset) in [
+        ("-10min", time::Duration::minutes(-10)),
+        ("-10m", time::Duration::minutes(-10)),
+        ("+1M", time::Duration::seconds(2_629_800)),
+        ("-1y", time::Duration::seconds(-31_557_600)),
+        ("+1w 2d", time::Duration::days(9)),
+        ("+1h30m", time::Duration::minutes(90)),
+        ("-1.5h", time::Duration::minutes(-90)),
+        ("+500ms 250usec", time::Duration::microseconds(500_250)),
+        ("-3 seconds", time::Duration::seconds(-3)),
+    ] {
+        let parsed = TimeSpec::parse_with_anchor(input, anchor).unwrap();
+
+        assert_eq!(
+            parsed,
+            TimeSpec::Point(anchor + offset, Zone::Local),
+            "{input}"
+        )
+    }
+}
+
+#[test]
+fn test_relative_invalid() {
+    let anchor = time::Date::from_calendar_date(2024, time::Month::August, 8)
+        .unwrap()
+        .midnight();
+
+    assert!(TimeSpec::parse_with_anchor("-10", anchor).is_err());
+    assert!(TimeSpec::parse_with_anchor("-10 parsecs", anchor).is_err());
+    assert!(TimeSpec::parse_with_anchor("10min", anchor).is_err());
+    assert!(TimeSpec::parse_with_anchor("+999999999999y", anchor).is_err());
+}
+
+#[test]
+fn test_relative_suffix() {
+    let anchor = time::Date::from_calendar_date(2024, time::Month::August, 8)
+        .unwrap()
+        .midnight()
+        .replace_time(time::Time::from_hms(12, 20, 45).unwrap());
+
+    for (suffixed, prefixed) in [
+        ("5min ago", "-5min"),
+        ("1h 30min ago", "-1h 30min"),
+        ("3h left", "+3h"),
+        ("2 days left", "+2 days"),
+    ] {
+        assert_eq!(
+            TimeSpec::parse_with_anchor(suffixed, anchor).unwrap(),
+            TimeSpec::parse_with_anchor(prefixed, anchor).unwrap(),
+            "{suffixed}"
+        )
+    }
+
+    assert!(TimeSpec::parse_with_anchor("5min", anchor).is_err());
+    assert!(TimeSpec::parse_with_anchor("-5min ago", anchor).is_err());
+}
+
+#[test]
+fn test_date_time_fractional() {
+    let anchor = time::Date::from_calendar_date(2023, time::Month::November, 11)
+        .unwrap()
+        .midnight()
+        .replace_time(time::Time::from_hms(12, 20, 45).unwrap());
+
+    let date = time::Date::from_calendar_date(2024, time::Month::August, 8).unwrap();
+
+    for (input, nano) in [
+        ("2024-08-08 14:10:11.123456", 123_456_000),
+        ("2024-08-08 14:10:11.5", 500_000_000),
+        ("2024-08-08 14:10:11.000000001", 1),
+    ] {
+        let target = date.with_time(time::Time::from_hms_nano(14, 10, 11, nano).unwrap());
+
+        let parsed = TimeSpec::parse_with_anchor(input, anchor).unwrap();
+
+        assert_eq!(parsed, TimeSpec::Point(target, Zone::Local), "{input}")
+    }
+
+    assert!(TimeSpec::parse_with_anchor("2024-08-08 14:10:11.1234567890", anchor).is_err());
+    assert!(TimeSpec::parse_with_anchor("2024-08-08 14:10:11.", anchor).is_err());
+    assert!(TimeSpec::parse_with_anchor("2024-08-08 14:10.5", anchor).is_err());
+}
+
+#[test]
+fn test_time_fractional() {
+    let anchor = time::Date::from_calendar_date(2024, time::Month::August, 8)
+        .unwrap()
+        .midnight()
+        .replace_time(time::Time::from_hms(12, 20, 45).unwrap());
+
+    let target = anchor.replace_time(time::Time::from_hms_nano(15, 27, 59, 250_000_000).unwrap());
+
+    let parsed = TimeSpec::parse_with_anchor("-15:27:59.25", anchor).unwrap();
+
+    assert_eq!(parsed, TimeSpec::Before(target, Zone::Local))
+}
+
+#[test]
+fn test_date_time_zone() {
+    let target = time::Date::from_calendar_date(2012, time::Month::November, 23)
+        .unwrap()
+        .with_time(time::Time::from_hms(11, 12, 13).unwrap());
+
+    let anchor = time::Date::from_calendar_date(2023, time::Month::November, 11)
+        .unwrap()
+        .midnight();
+
+    for (input, offset) in [
+        ("2012-11-23 11:12:13 UTC", time::UtcOffset::UTC),
+        ("2012-11-23 11:12:13 Z", time::UtcOffset::UTC),
+        (
+            "2012-11-23 11:12:13 +02:00",
+            time::UtcOffset::from_hms(2, 0, 0).unwrap(),
+        ),
+        (
+            "2012-11-23 11:12:13 +0200",
+            time::UtcOffset::from_hms(2, 0, 0).unwrap(),
+        ),
+        (
+            "2012-11-23 11:12:13 -05",
+            time::UtcOffset::from_hms(-5, 0, 0).unwrap(),
+        ),
+        (
+            "2012-11-23 11:12:13 -03:30",
+            time::UtcOffset::from_hms(-3, -30, 0).unwrap(),
+        ),
+    ] {
+        let parsed = TimeSpec::parse_with_anchor(input, anchor).unwrap();
+
+        assert_eq!(
+            parsed,
+            TimeSpec::Point(target, Zone::Fixed(offset)),
+            "{input}"
+        )
+    }
+
+    let parsed = TimeSpec::parse_with_anchor("+2012-11-23 11:12:13 Europe/Paris", anchor).unwrap();
+    assert_eq!(
+        parsed,
+        TimeSpec::After(target, Zone::Named(String::from("Europe/Paris")))
+    );
+
+    // Any timezone file of the zoneinfo directory is accepted, as systemd does
+    for name in ["US/Eastern", "GMT", "EST5EDT", "Japan"] {
+        let input = format!("2012-11-23 11:12:13 {name}");
+        assert_eq!(
+            TimeSpec::parse_with_anchor(&input, anchor).unwrap(),
+            TimeSpec::Point(target, Zone::Named(String::from(name))),
+            "{input}"
+        );
+    }
+
+    // Nothing is read outside of the zoneinfo directory, nor from files that are not TZif data
+    for input in [
+        "2012-11-23 11:12:13 Mars/Olympus",
+        "2012-11-23 11:12:13 EST5",
+        "2012-11-23 11:12:13 zone.tab",
+        "2012-11-23 11:12:13 src/lib.rs",
+    ] {
+        assert!(
+            TimeSpec::parse_with_anchor(input, anchor).is_err(),
+            "{input}"
+        );
+    }
+    for name in [
+        "Europe/../Europe/Paris",
+        "/usr/share/zoneinfo/Europe/Paris",
+        "./GMT",
+    ] {
+        assert!(
+            Zone::Named(String::from(name)).time_zone().is_err(),
+            "{name}"
+        );
+    }
+    assert!(TimeSpec::parse_with_anchor("2012-11-23 11:12:13 +26:00", anchor).is_err());
+}
+
+#[test]
+fn test_date_zone() {
+    let target = time::Date::from_calendar_date(2012, time::Month::November, 23)
+        .unwrap()
+        .midnight();
+
+    let anchor = time::Date::from_calendar_date(2023, time::Month::November, 11)
+        .unwrap()
+        .midnight();
+
+    let parsed = TimeSpec::parse_with_anchor("2012-11-23 UTC", anchor).unwrap();
+
+    assert_eq!(
+        parsed,
+        TimeSpec::Point(target, Zone::Fixed(time::UtcOffset::UTC))
+    )
+}
+
+#[test]
+fn test_time_zone() {
+    let anchor = time::Date::from_calendar_date(2024, time::Month::August, 8)
+        .unwrap()
+        .midnight()
+        .replace_time(time::Time::from_hms(12, 20, 45).unwrap());
+
+    let target = anchor.replace_time(time::Time::from_hms(15, 28, 0).unwrap());
+
+    let parsed = TimeSpec::parse_with_anchor("15:28 +01:00", anchor).unwrap();
+
+    assert_eq!(
+        parsed,
+        TimeSpec::Point(
+            target,
+            Zone::Fixed(time::UtcOffset::from_hms(1, 0, 0).unwrap())
+        )
+    )
+}
+
+#[test]
+fn test_epoch() {
+    let anchor = time::Date::from_calendar_date(2023, time::Month::November, 11)
+        .unwrap()
+        .midnight();
+
+    let target = time::Date::from_calendar_date(2014, time::Month::March, 25)
+        .unwrap()
+        .with_time(time::Time::from_hms(2, 59, 56).unwrap());
+
+    let parsed = TimeSpec::parse_with_anchor("@1395716396", anchor).unwrap();
+    assert_eq!(
+        parsed,
+        TimeSpec::Point(target, Zone::Fixed(time::UtcOffset::UTC))
+    );
+
+    let parsed = TimeSpec::parse_with_anchor("@1395716396.123", anchor).unwrap();
+    assert_eq!(
+        parsed,
+        TimeSpec::Point(
+            target + time::Duration::milliseconds(123),
+            Zone::Fixed(time::UtcOffset::UTC)
+        )
+    );
+
+    assert!(TimeSpec::parse_with_anchor("@", anchor).is_err());
+    assert!(TimeSpec::parse_with_anchor("@99999999999999999", anchor).is_err());
+}
+
+#[test]
+fn test_weekday() {
+    let anchor = time::Date::from_calendar_date(2023, time::Month::November, 11)
+        .unwrap()
+        .midnight();
+
+    let target = time::Date::from_calendar_date(2012, time::Month::November, 23)
+        .unwrap()
+        .with_time(time::Time::from_hms(11, 12, 13).unwrap());
+
+    for input in [
+        "Fri 2012-11-23 11:12:13",
+        "Friday 2012-11-23 11:12:13",
+        "fri 2012-11-23 11:12:13",
+    ] {
+        let parsed = TimeSpec::parse_with_anchor(input, anchor).unwrap();
+
+        assert_eq!(parsed, TimeSpec::Point(target, Zone::Local), "{input}")
+    }
+
+    let parsed = TimeSpec::parse_with_anchor("-Fri 2012-11-23 UTC", anchor).unwrap();
+    assert_eq!(
+        parsed,
+        TimeSpec::Before(
+            target.replace_time(time::Time::MIDNIGHT),
+            Zone::Fixed(time::UtcOffset::UTC)
+        )
+    );
+}
+
+#[test]
+fn test_weekday_mismatch() {
+    let anchor = time::Date::from_calendar_date(2023, time::Month::November, 11)
+        .unwrap()
+        .midnight();
+
+    match TimeSpec::parse_with_anchor("Thu 2012-11-23 11:12:13", anchor) {
+        Err(IntervalleError::WeekdayMismatch { given, actual, .. }) => {
+            assert_eq!(given, time::Weekday::Thursday);
+            assert_eq!(actual, time::Weekday::Friday);
+        }
+        other => panic!("unexpected result: {other:?}"),
+    }
+
+    assert!(TimeSpec::parse_with_anchor("Fri 11:12:13", anchor).is_err());
+}
+
+#[test]
+fn test_rfc3339() {
+    let anchor = time::Date::from_calendar_date(2023, time::Month::November, 11)
+        .unwrap()
+        .midnight();
+
+    let date = time::Date::from_calendar_date(2024, time::Month::August, 8).unwrap();
+    let utc = Zone::Fixed(time::UtcOffset::UTC);
+    let plus_two = Zone::Fixed(time::UtcOffset::from_hms(2, 0, 0).unwrap());
+
+    for (input, time, zone) in [
+        ("2024-08-08T14:10:11Z", (14, 10, 11, 0), utc.clone()),
+        ("2024-08-08t14:10:11z", (14, 10, 11, 0), utc.clone()),
+        (
+            "2024-08-08T14:10:11.5+02:00",
+            (14, 10, 11, 500_000_000),
+            plus_two.clone(),
+        ),
+        (
+            "2024-08-08 14:10:11+02:00",
+            (14, 10, 11, 0),
+            plus_two.clone(),
+        ),
+        ("2024-08-08T14:10:11", (14, 10, 11, 0), Zone::Local),
+        ("2024-08-08T14:10 UTC", (14, 10, 0, 0), utc.clone()),
+    ] {
+        let (hour, minute, second, nano) = time;
+        let target = date.with_time(time::Time::from_hms_nano(hour, minute, second, nano).unwrap());
+
+        let parsed = TimeSpec::parse_with_anchor(input, anchor).unwrap();
+
+        assert_eq!(parsed, TimeSpec::Point(target, zone), "{input}")
+    }
+
+    assert!(TimeSpec::parse_with_anchor("2024-08-08T", anchor).is_err());
+    assert!(TimeSpec::parse_with_anchor("2024-08-08T14:10:11+2:00", anchor).is_err());
+
+    // Only times take a glued timezone, a bare date takes one after a space
+    for input in ["2024-08-08-05", "2024-08-08Z", "2024-08-08+02:00"] {
+        assert!(
+            TimeSpec::parse_with_anchor(input, anchor).is_err(),
+            "{input}"
+        );
+    }
+    assert_eq!(
+        TimeSpec::parse_with_anchor("2024-08-08 +02:00", anchor).unwrap(),
+        TimeSpec::Point(date.midnight(), plus_two)
+    );
+}
+
+#[test]
+fn test_resolve() {
+    let paris = tz::TimeZone::from_posix_tz("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+    let anchor = time::Date::from_calendar_date(2024, time::Month::August, 8)
+        .unwrap()
+        .midnight();
+
+    for (input, expected) in [
+        ("2024-08-08 12:00", "2024-08-08 10:00 UTC"),
+        ("2024-01-08 12:00", "2024-01-08 11:00 UTC"),
+        ("2024-03-31 01:59:59", "2024-03-31 00:59:59 UTC"),
+        ("2024-03-31 03:00", "2024-03-31 01:00 UTC"),
+        ("2024-10-27 03:00", "2024-10-27 02:00 UTC"),
+        ("2024-08-08 12:00 +05:00", "2024-08-08 07:00 UTC"),
+        ("@1723118400", "2024-08-08 12:00 UTC"),
+    ] {
+        let expected = TimeSpec::parse_with_anchor(expected, anchor)
+            .unwrap()
+            .resolve(&paris)
+            .unwrap();
+
+        let resolved = TimeSpec::parse_with_anchor(input, anchor)
+            .unwrap()
+            .resolve(&paris)
+            .unwrap();
+
+        assert_eq!(resolved, expected, "{input}");
+    }
+}
+
+#[test]
+fn test_resolve_offset() {
+    let paris = tz::TimeZone::from_posix_tz("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+    let anchor = time::Date::from_calendar_date(2024, time::Month::January, 8)
+        .unwrap()
+        .midnight();
+
+    let resolved = TimeSpec::parse_with_anchor("2024-08-08 12:00", anchor)
+        .unwrap()
+        .resolve(&paris)
+        .unwrap();
+
+    assert_eq!(
+        resolved.offset(),
+        time::UtcOffset::from_hms(2, 0, 0).unwrap()
+    );
+
+    let resolved = TimeSpec::parse_with_anchor("2024-08-08 12:00 Europe/Paris", anchor)
+        .unwrap()
+        .resolve(&tz::TimeZone::utc())
+        .unwrap();
+
+    assert_eq!(
+        resolved.offset(),
+        time::UtcOffset::from_hms(2, 0, 0).unwrap()
+    );
+}
+
+#[test]
+fn test_system_time() {
+    let anchor = time::Date::from_calendar_date(2024, time::Month::August, 8)
+        .unwrap()
+        .midnight();
+
+    let system_time = TimeSpec::parse_with_anchor("@1395716396.5", anchor)
+        .unwrap()
+        .to_system_time(&tz::TimeZone::utc())
+        .unwrap();
+
+    assert_eq!(
+        system_time,
+        SystemTime::UNIX_EPOCH + std::time::Duration::from_millis(1_395_716_396_500)
+    );
+}
+
+#[test]
+fn test_dst_gap() {
+    let paris = tz::TimeZone::from_posix_tz("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+    let anchor = time::Date::from_calendar_date(2024, time::Month::August, 8)
+        .unwrap()
+        .midnight();
+
+    let timespec = TimeSpec::parse_with_anchor("2024-03-31 02:30", anchor).unwrap();
+    let before = TimeSpec::parse_with_anchor("2024-03-31 00:30 UTC", anchor)
+        .unwrap()
+        .resolve(&paris)
+        .unwrap();
+    let after = TimeSpec::parse_with_anchor("2024-03-31 01:30 UTC", anchor)
+        .unwrap()
+        .resolve(&paris)
+        .unwrap();
+
+    let resolution = timespec.locate(&paris).unwrap();
+    assert!(matches!(resolution, Resolution::Nonexistent(..)));
+    assert_eq!(
+        resolution.earliest().offset(),
+        time::UtcOffset::from_hms(1, 0, 0).unwrap()
+    );
+    assert_eq!(
+        resolution.latest().offset(),
+        time::UtcOffset::from_hms(2, 0, 0).unwrap()
+    );
+
+    assert_eq!(
+        timespec
+            .resolve_with(&paris, Disambiguation::Earliest)
+            .unwrap(),
+        before
+    );
+    assert_eq!(
+        timespec
+            .resolve_with(&paris, Disambiguation::Latest)
+            .unwrap(),
+        after
+    );
+    assert!(matches!(
+        timespec.resolve_with(&paris, Disambiguation::Reject),
+        Err(IntervalleError::NonexistentLocalTime(dtime)) if dtime == timespec.datetime()
+    ));
+}
+
+#[test]
+fn test_dst_fold() {
+    let paris = tz::TimeZone::from_posix_tz("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+    let anchor = time::Date::from_calendar_date(2024, time::Month::August, 8)
+        .unwrap()
+        .midnight();
+
+    let timespec = TimeSpec::parse_with_anchor("2024-10-27 02:30", anchor).unwrap();
+    let first = TimeSpec::parse_with_anchor("2024-10-27 02:30 +02:00", anchor)
+        .unwrap()
+        .resolve(&paris)
+        .unwrap();
+    let second = TimeSpec::parse_with_anchor("2024-10-27 02:30 +01:00", anchor)
+        .unwrap()
+        .resolve(&paris)
+        .unwrap();
+
+    assert_eq!(
+        timespec.locate(&paris).unwrap(),
+        Resolution::Ambiguous(first, second)
+    );
+    assert_eq!(timespec.resolve(&paris).unwrap(), first);
+    assert_eq!(
+        timespec
+            .resolve_with(&paris, Disambiguation::Latest)
+            .unwrap(),
+        second
+    );
+    assert!(matches!(
+        timespec.resolve_with(&paris, Disambiguation::Reject),
+        Err(IntervalleError::AmbiguousLocalTime(_))
+    ));
+
+    let timespec = TimeSpec::parse_with_anchor("2024-10-27 03:30", anchor).unwrap();
+    assert!(matches!(
+        timespec.locate(&paris).unwrap(),
+        Resolution::Single(_)
+    ));
+}
+
+#[test]
+fn test_invalid_date() {
+    let anchor = time::Date::from_calendar_date(2024, time::Month::August, 8)
+        .unwrap()
+        .midnight();
+
+    match TimeSpec::parse_with_anchor("2024-13-08", anchor) {
+        Err(IntervalleError::InvalidMonth { month, span }) => {
+            assert_eq!(month, 13);
+            assert_eq!(span.text(), "13");
+        }
+        other => panic!("unexpected result: {other:?}"),
+    }
+
+    match TimeSpec::parse_with_anchor("+2023-02-29 10:00", anchor) {
+        Err(IntervalleError::InvalidDay {
+            day,
+            month,
+            year,
+            span,
+        }) => {
+            assert_eq!((day, month, year), (29, time::Month::February, 2023));
+            assert_eq!(span.range, 9..11);
+        }
+        other => panic!("unexpected result: {other:?}"),
+    }
+}
+
+#[test]
+fn test_invalid_time() {
+    let anchor = time::Date::from_calendar_date(2024, time::Month::August, 8)
+        .unwrap()
+        .midnight();
+
+    for (input, expected, text) in [
+        ("25:00", TimeComponent::Hour, "25"),
+        ("2024-08-08 12:61", TimeComponent::Minute, "61"),
+        ("2024-08-08T12:30:75Z", TimeComponent::Second, "75"),
+    ] {
+        match TimeSpec::parse_with_anchor(input, anchor) {
+            Err(IntervalleError::InvalidTime {
+                component, span, ..
+            }) => {
+                assert_eq!(component, expected);
+                assert_eq!(span.text(), text);
+            }
+            other => panic!("unexpected result for {input}: {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn test_unknown_keyword() {
+    let anchor = time::Date::from_calendar_date(2024, time::Month::August, 8)
+        .unwrap()
+        .midnight();
+
+    match TimeSpec::parse_with_anchor("-tomorow", anchor) {
+        Err(IntervalleError::UnknownKeyword {
+            span,
+            expected,
+            suggestion,
+        }) => {
+            assert_eq!(span.range, 1..8);
+            assert!(expected.contains(&"tomorrow"));
+            assert_eq!(suggestion, Some("tomorrow"));
+        }
+        other => panic!("unexpected result: {other:?}"),
+    }
+}
+
+#[test]
+fn test_trailing_input() {
+    let anchor = time::Date::from_calendar_date(2024, time::Month::August, 8)
+        .unwrap()
+        .midnight();
+
+    match TimeSpec::parse_with_anchor("2024-08-08 14:10:11 extra", anchor) {
+        Err(IntervalleError::TrailingInput { span, closest }) => {
+            assert_eq!(span.text(), "extra");
+            assert_eq!(closest, vec!["2024-08-08 14:10:11 UTC"]);
+        }
+        other => panic!("unexpected result: {other:?}"),
+    }
+
+    match TimeSpec::parse_with_anchor("yesterday ", anchor) {
+        Err(IntervalleError::TrailingInput { span, .. }) => assert_eq!(span.range, 9..10),
+        other => panic!("unexpected result: {other:?}"),
+    }
+}
+
+#[test]
+fn test_overflow() {
+    let anchor = time::Date::from_calendar_date(2024, time::Month::August, 8)
+        .unwrap()
+        .midnight();
+
+    for (input, text) in [
+        ("+999999999999y", "999999999999y"),
+        ("+100000y", "+100000y"),
+        (
+            "99999999999999999999999999h ago",
+            "99999999999999999999999999h",
+        ),
+        ("@99999999999999999999", "99999999999999999999"),
+    ] {
+        match TimeSpec::parse_with_anchor(input, anchor) {
+            Err(IntervalleError::Overflow { span }) => assert_eq!(span.text(), text),
+            other => panic!("unexpected result for {input}: {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn test_display() {
+    let anchor = time::Date::from_calendar_date(2024, time::Month::August, 8)
+        .unwrap()
+        .with_hms(14, 10, 0)
+        .unwrap();
+
+    for (timespec, canonical) in [
+        ("+2024-08-08 14:10", "+2024-08-08 14:10:00"),
+        ("-yesterday", "-2024-08-07 00:00:00"),
+        ("now", "2024-08-08 14:10:00"),
+        ("-1h 30min", "2024-08-08 12:40:00"),
+        ("12:00:00.250", "2024-08-08 12:00:00.25"),
+        ("2024-08-08T14:10:11Z", "2024-08-08 14:10:11 UTC"),
+        (
+            "2024-08-08T14:10:11.5-05:30",
+            "2024-08-08 14:10:11.5 -05:30",
+        ),
+        ("@1723126211", "2024-08-08 14:10:11 UTC"),
+        (
+            "2024-08-08 14:10 +01:02:03",
+            "2024-08-08 14:10:00 +01:02:03",
+        ),
+        ("2024-08-08 14:10 -000921", "2024-08-08 14:10:00 -00:09:21"),
+        (
+            "Thu 2024-08-08 Europe/Paris",
+            "2024-08-08 00:00:00 Europe/Paris",
+        ),
+    ] {
+        let parsed = TimeSpec::parse_with_anchor(timespec, anchor).unwrap();
+        assert_eq!(parsed.to_string(), canonical, "{timespec}");
+    }
+}
+
+#[test]
+fn test_display_round_trip() {
+    // A xorshift generator, to check the property over a reproducible set of timespecs
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let mut random = move |bound: u64| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state % bound
+    };
+
+    let anchor = time::Date::from_calendar_date(2024, time::Month::August, 8)
+        .unwrap()
+        .midnight();
+    let first = time::Date::from_calendar_date(0, time::Month::January, 1)
+        .unwrap()
+        .to_julian_day();
+    let last = time::Date::from_calendar_date(9999, time::Month::December, 31)
+        .unwrap()
+        .to_julian_day();
+
+    for _ in 0..2000 {
+        let date =
+            time::Date::from_julian_day(first + random((last - first + 1) as u64) as i32).unwrap();
+        let nanosecond = match random(3) {
+            0 => 0,
+            1 => random(1_000) as u32 * 1_000_000,
+            _ => random(1_000_000_000) as u32,
+        };
+        let dtime = date
+            .with_hms_nano(
+                random(24) as u8,
+                random(60) as u8,
+                random(60) as u8,
+                nanosecond,
+            )
+            .unwrap();
+
+        let zone = match random(4) {
+            0 => Zone::Local,
+            1 => Zone::Fixed(time::UtcOffset::UTC),
+            2 => Zone::Fixed(
+                time::UtcOffset::from_whole_seconds(random(2 * 93_599 + 1) as i32 - 93_599)
+                    .unwrap(),
+            ),
+            _ => Zone::Named(String::from(
+                ["Europe/Paris", "America/New_York", "Asia/Kolkata"][random(3) as usize],
+            )),
+        };
+
+        let timespec = match random(3) {
+            0 => TimeSpec::After(dtime, zone),
+            1 => TimeSpec::Before(dtime, zone),
+            _ => TimeSpec::Point(dtime, zone),
+        };
+
+        assert_eq!(
+            TimeSpec::parse_with_anchor(&timespec.to_string(), anchor).unwrap(),
+            timespec,
+            "{timespec}"
+        );
+    }
+}
+
+#[test]
+fn test_from_str() {
+    let parsed: TimeSpec = "2024-08-08 14:10:11 UTC".parse().unwrap();
+    assert_eq!(
+        parsed,
+        TimeSpec::Point(
+            time::Date::from_calendar_date(2024, time::Month::August, 8)
+                .unwrap()
+                .with_hms(14, 10, 11)
+                .unwrap(),
+            Zone::Fixed(time::UtcOffset::UTC)
+        )
+    );
+    assert_eq!(
+        TimeSpec::try_from("2024-08-08 14:10:11 UTC").unwrap(),
+        parsed
+    );
+
+    assert!("yesturday".parse::<TimeSpec>().is_err());
+    assert!(TimeSpec::try_from("").is_err());
 }
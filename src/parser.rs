@@ -0,0 +1,463 @@
+//! Grammar shared by the parsers of this crate
+//!
+//! The building blocks are written as macros expanding at the call site, which must have the
+//! `winnow` items they use in scope.
+
+use crate::{
+    error::{closest_forms, misspelling, Failure, FailureKind, Grammar},
+    Anchor, IntervalleError, Span, TimeSpec, Zone,
+};
+use time::{ext::NumericalDuration, Duration, PrimitiveDateTime as DateTime, Weekday};
+use winnow::{
+    ascii::{digit1, space0, space1, Caseless},
+    combinator::{alt, cut_err, not, opt, peek, preceded, repeat, terminated},
+    error::{ContextError, ErrMode, StrContext, StrContextValue},
+    prelude::*,
+    token::{literal, one_of, take_while},
+};
+
+/// Run `parser` over the whole of `input`, which must be consumed entirely
+///
+/// Leftover input is reported along with the forms of `grammar` closest to `input`, and syntax
+/// errors caused by a misspelled word point at that word with the closest correct spelling.
+pub(crate) fn complete<'i, O>(
+    mut parser: impl Parser<&'i str, O, ContextError>,
+    input: &'i str,
+    grammar: &Grammar,
+) -> Result<O, IntervalleError> {
+    let mut rest = input;
+    let result = parser.parse_next(&mut rest);
+    let offset = input.len() - rest.len();
+
+    match result {
+        Ok(_) if !rest.is_empty() => {
+            let start = match rest.trim_start() {
+                "" => offset,
+                trimmed => input.len() - trimmed.len(),
+            };
+
+            Err(IntervalleError::TrailingInput {
+                span: Span::new(input, start..input.len(), grammar),
+                closest: closest_forms(input, grammar.forms),
+            })
+        }
+        Ok(output) => Ok(output),
+        Err(ErrMode::Backtrack(error) | ErrMode::Cut(error)) => {
+            Err(IntervalleError::from_context(input, offset, error, grammar))
+        }
+        Err(ErrMode::Incomplete(_)) => unreachable!("the input is never partial"),
+    }
+    .map_err(|error| match error {
+        IntervalleError::Syntax { .. }
+        | IntervalleError::UnknownKeyword { .. }
+        | IntervalleError::TrailingInput { .. } => misspelling(input, grammar).unwrap_or(error),
+        error => error,
+    })
+}
+
+/// Check the output of `parser`, committing to this branch of the grammar if the check fails
+pub(crate) fn validate<'i, O, O2>(
+    parser: impl Parser<&'i str, O, ContextError>,
+    check: impl FnMut(O) -> Result<O2, Failure>,
+) -> impl Parser<&'i str, O2, ContextError> {
+    let mut parser = parser.try_map(check);
+
+    move |input: &mut &'i str| {
+        parser.parse_next(input).map_err(|error| match error {
+            ErrMode::Backtrack(error)
+                if error.cause().is_some_and(|cause| cause.is::<Failure>()) =>
+            {
+                ErrMode::Cut(error)
+            }
+            error => error,
+        })
+    }
+}
+
+pub(crate) fn yesterday(anchor: DateTime) -> DateTime {
+    anchor
+        .date()
+        .midnight()
+        .checked_sub(1.days())
+        .expect("Unreacheable, we allow 4 digit years and the library supports i32")
+}
+
+pub(crate) fn tomorrow(anchor: DateTime) -> DateTime {
+    anchor
+        .date()
+        .midnight()
+        .checked_add(1.days())
+        .expect("Unreacheable, we allow 4 digit years and the library supports i32")
+}
+
+pub(crate) const NSEC_PER_USEC: i128 = 1_000;
+pub(crate) const NSEC_PER_MSEC: i128 = 1_000 * NSEC_PER_USEC;
+pub(crate) const NSEC_PER_SEC: i128 = 1_000 * NSEC_PER_MSEC;
+pub(crate) const NSEC_PER_MINUTE: i128 = 60 * NSEC_PER_SEC;
+pub(crate) const NSEC_PER_HOUR: i128 = 60 * NSEC_PER_MINUTE;
+pub(crate) const NSEC_PER_DAY: i128 = 24 * NSEC_PER_HOUR;
+pub(crate) const NSEC_PER_WEEK: i128 = 7 * NSEC_PER_DAY;
+/// systemd counts a month as 30.44 days
+pub(crate) const NSEC_PER_MONTH: i128 = 2_629_800 * NSEC_PER_SEC;
+/// systemd counts a year as 365.25 days
+pub(crate) const NSEC_PER_YEAR: i128 = 31_557_600 * NSEC_PER_SEC;
+
+/// Scale a decimal quantity of `unit` nanoseconds, returning `None` on overflow
+pub(crate) fn nanoseconds(int: &str, frac: Option<&str>, unit: i128) -> Option<i128> {
+    let mut total = int.parse::<i128>().ok()?.checked_mul(unit)?;
+
+    if let Some(frac) = frac {
+        let scale = 10i128.checked_pow(u32::try_from(frac.len()).ok()?)?;
+        total = total.checked_add(frac.parse::<i128>().ok()?.checked_mul(unit)? / scale)?;
+    }
+
+    Some(total)
+}
+
+pub(crate) fn duration_from_nanoseconds(total: i128) -> Option<Duration> {
+    let seconds = i64::try_from(total / NSEC_PER_SEC).ok()?;
+    let nanoseconds = (total % NSEC_PER_SEC) as i32;
+
+    Some(Duration::new(seconds, nanoseconds))
+}
+
+macro_rules! digits {
+    ($len:expr, $dest:ty) => {
+        digit1
+            .verify(|s: &str| s.len() == $len)
+            .try_map(str::parse::<$dest>)
+            .context(StrContext::Label("digit count"))
+    };
+}
+
+macro_rules! date {
+    () => {
+        $crate::parser::validate(
+            (
+                digits!(4, u16),
+                preceded(
+                    cut_err("-")
+                        .context(StrContext::Label("date delimiter"))
+                        .context(StrContext::Expected(StrContextValue::CharLiteral('-'))),
+                    digits!(2, u8),
+                ),
+                preceded(
+                    cut_err("-")
+                        .context(StrContext::Label("date delimiter"))
+                        .context(StrContext::Expected(StrContextValue::CharLiteral('-'))),
+                    digits!(2, u8),
+                ),
+            ),
+            |(year, month, day)| {
+                let year = i32::from(year);
+                let month = time::Month::try_from(month).map_err(|_| {
+                    $crate::error::Failure::new(
+                        $crate::error::FailureKind::InvalidMonth(month),
+                        5..7,
+                    )
+                })?;
+
+                time::Date::from_calendar_date(year, month, day).map_err(|_| {
+                    $crate::error::Failure::new(
+                        $crate::error::FailureKind::InvalidDay { day, month, year },
+                        8..10,
+                    )
+                })
+            },
+        )
+        .map(|d| d.midnight())
+        .context(StrContext::Label("date format"))
+    };
+}
+
+/// Fractional part of a second, up to nanosecond precision, as a nanosecond count
+macro_rules! fraction {
+    () => {
+        digit1
+            .verify(|s: &str| s.len() <= 9)
+            .try_map(|s: &str| s.parse::<u32>().map(|n| n * 10u32.pow(9 - s.len() as u32)))
+            .context(StrContext::Label("fractional seconds"))
+    };
+}
+
+macro_rules! time {
+    () => {
+        $crate::parser::validate(
+            (
+                digits!(2, u8),
+                preceded(
+                    cut_err(":")
+                        .context(StrContext::Label("time delimiter"))
+                        .context(StrContext::Expected(StrContextValue::CharLiteral(':'))),
+                    cut_err(digits!(2, u8)),
+                ),
+                opt(preceded(
+                    literal(":")
+                        .context(StrContext::Label("time delimiter"))
+                        .context(StrContext::Expected(StrContextValue::CharLiteral(':'))),
+                    cut_err((digits!(2, u8), opt(preceded(".", fraction!())))),
+                )),
+            ),
+            |(hour, min, sec)| {
+                let (sec, nano) = sec.unwrap_or((0, None));
+
+                time::Time::from_hms_nano(hour, min, sec, nano.unwrap_or(0)).map_err(|_| {
+                    let (component, value, range) = match (hour, min) {
+                        (24.., _) => ($crate::TimeComponent::Hour, hour, 0..2),
+                        (_, 60..) => ($crate::TimeComponent::Minute, min, 3..5),
+                        _ => ($crate::TimeComponent::Second, sec, 6..8),
+                    };
+
+                    $crate::error::Failure::new(
+                        $crate::error::FailureKind::InvalidTime { component, value },
+                        range,
+                    )
+                })
+            },
+        )
+    };
+}
+
+/// Time units as understood by systemd.time(7), longest spellings first
+macro_rules! unit {
+    () => {
+        alt((
+            alt(("usec", "us", "µs", "μs")).value($crate::parser::NSEC_PER_USEC),
+            alt(("msec", "ms")).value($crate::parser::NSEC_PER_MSEC),
+            alt(("seconds", "second", "sec", "s")).value($crate::parser::NSEC_PER_SEC),
+            alt(("minutes", "minute", "min")).value($crate::parser::NSEC_PER_MINUTE),
+            alt(("months", "month", "M")).value($crate::parser::NSEC_PER_MONTH),
+            "m".value($crate::parser::NSEC_PER_MINUTE),
+            alt(("hours", "hour", "hr", "h")).value($crate::parser::NSEC_PER_HOUR),
+            alt(("days", "day", "d")).value($crate::parser::NSEC_PER_DAY),
+            alt(("weeks", "week", "w")).value($crate::parser::NSEC_PER_WEEK),
+            alt(("years", "year", "y")).value($crate::parser::NSEC_PER_YEAR),
+        ))
+        .context(StrContext::Label("time unit"))
+    };
+}
+
+/// A single `<number>[.<fraction>] <unit>` component of a time span, in nanoseconds
+macro_rules! span_component {
+    () => {
+        (
+            digit1,
+            opt(preceded(".", digit1)),
+            preceded(space0, unit!()),
+        )
+            .map(|(int, frac, unit)| $crate::parser::nanoseconds(int, frac, unit))
+    };
+}
+
+/// A time span made of one or more components, e.g. `1h 30min` or `2days`
+macro_rules! timespan {
+    () => {
+        $crate::parser::validate(
+            (
+                span_component!(),
+                repeat(0.., preceded(space0, span_component!())).fold(
+                    || Some(0i128),
+                    |acc: Option<i128>, n: Option<i128>| acc?.checked_add(n?),
+                ),
+            )
+                .with_taken(),
+            |((first, rest), taken): ((Option<i128>, Option<i128>), &str)| {
+                first
+                    .zip(rest)
+                    .and_then(|(first, rest)| first.checked_add(rest))
+                    .and_then($crate::parser::duration_from_nanoseconds)
+                    .ok_or_else(|| $crate::parser::overflow(taken))
+            },
+        )
+        .context(StrContext::Label("time span"))
+    };
+}
+
+/// A date, optionally followed by a time after a space or an ISO 8601 `T` separator
+///
+/// Only a time may have a timezone glued to it: `2024-08-08-05` is not a date at -05:00.
+macro_rules! date_time {
+    () => {
+        alt((
+            (
+                date!(),
+                preceded(
+                    alt((
+                        (
+                            literal(" ")
+                                .context(StrContext::Expected(StrContextValue::CharLiteral(' '))),
+                            peek(digit1),
+                        )
+                            .void(),
+                        one_of(['T', 't'])
+                            .context(StrContext::Expected(StrContextValue::CharLiteral('T')))
+                            .void(),
+                    )),
+                    cut_err(time!()).context(StrContext::Label("time")),
+                ),
+            )
+                .map(|(pdate, ptime)| pdate.replace_time(ptime))
+                .context(StrContext::Label("time_and_date")),
+            terminated(date!(), not(one_of(['Z', 'z', '+', '-']))),
+        ))
+    };
+}
+
+/// An English weekday name, abbreviated or in full
+macro_rules! weekday {
+    () => {
+        alt((
+            alt((Caseless("monday"), Caseless("mon"))).value(time::Weekday::Monday),
+            alt((Caseless("tuesday"), Caseless("tue"))).value(time::Weekday::Tuesday),
+            alt((Caseless("wednesday"), Caseless("wed"))).value(time::Weekday::Wednesday),
+            alt((Caseless("thursday"), Caseless("thu"))).value(time::Weekday::Thursday),
+            alt((Caseless("friday"), Caseless("fri"))).value(time::Weekday::Friday),
+            alt((Caseless("saturday"), Caseless("sat"))).value(time::Weekday::Saturday),
+            alt((Caseless("sunday"), Caseless("sun"))).value(time::Weekday::Sunday),
+        ))
+        .context(StrContext::Label("weekday"))
+    };
+}
+
+/// Seconds since the Unix epoch, with an optional fraction, as a UTC date and time
+macro_rules! epoch {
+    () => {
+        $crate::parser::validate(
+            (digit1, opt(preceded(".", fraction!()))),
+            |(seconds, nano): (&str, Option<u32>)| {
+                seconds
+                    .parse::<i64>()
+                    .ok()
+                    .and_then(|seconds| time::OffsetDateTime::from_unix_timestamp(seconds).ok())
+                    .map(|t| t + time::Duration::nanoseconds(nano.unwrap_or(0).into()))
+                    .ok_or_else(|| $crate::parser::overflow(seconds))
+            },
+        )
+        .map(|t| time::PrimitiveDateTime::new(t.date(), t.time()))
+        .context(StrContext::Label("epoch timestamp"))
+    };
+}
+
+/// A numeric UTC offset: `+02:00`, `+0200` or `+02`, with seconds for offsets that have them,
+/// e.g. `+00:09:21`
+macro_rules! utc_offset {
+    () => {
+        (
+            alt(("+".value(1), "-".value(-1))),
+            take_while(2, '0'..='9').try_map(str::parse::<i8>),
+            opt((
+                preceded(opt(":"), take_while(2, '0'..='9').try_map(str::parse::<i8>)),
+                opt(preceded(
+                    opt(":"),
+                    take_while(2, '0'..='9').try_map(str::parse::<i8>),
+                )),
+            )),
+        )
+            .try_map(|(sign, hours, rest)| {
+                let (minutes, seconds) = rest.unwrap_or((0, None));
+
+                time::UtcOffset::from_hms(sign * hours, sign * minutes, sign * seconds.unwrap_or(0))
+            })
+            .context(StrContext::Label("UTC offset"))
+    };
+}
+
+/// A timezone suffix: `UTC`, `Z`, a numeric offset or an IANA timezone name, e.g.
+/// `Europe/Paris`, found in the zoneinfo directory
+macro_rules! zone {
+    () => {
+        alt((
+            utc_offset!().map($crate::Zone::Fixed),
+            take_while(1.., |c: char| {
+                c.is_ascii_alphanumeric() || "/_+-".contains(c)
+            })
+            .verify(|name: &str| name.starts_with(|c: char| c.is_ascii_alphabetic()))
+            .verify_map(|name: &str| match name {
+                "UTC" | "Z" => Some($crate::Zone::Fixed(time::UtcOffset::UTC)),
+                name => $crate::zone::load(name)
+                    .ok()
+                    .map(|_| $crate::Zone::Named(name.to_owned())),
+            }),
+        ))
+        .context(StrContext::Label("timezone"))
+    };
+}
+
+/// The failure raised when `taken` does not fit in the supported range of dates
+pub(crate) fn overflow(taken: &str) -> Failure {
+    Failure::new(FailureKind::Overflow, 0..taken.len())
+}
+
+/// A complete timespec, resolving relative forms against `anchor`
+pub(crate) fn timespec<'i>(anchor: Anchor) -> impl Parser<&'i str, TimeSpec, ContextError> {
+    let wall = anchor.wall();
+
+    alt((
+        validate(
+            preceded("+", timespan!()).with_taken(),
+            move |(span, taken)| anchor.shift(span).ok_or_else(|| overflow(taken)),
+        )
+        .map(|(dtime, zone)| TimeSpec::Point(dtime, zone)),
+        validate(
+            preceded("-", timespan!()).with_taken(),
+            move |(span, taken)| anchor.shift(-span).ok_or_else(|| overflow(taken)),
+        )
+        .map(|(dtime, zone)| TimeSpec::Point(dtime, zone)),
+        validate(
+            terminated(timespan!(), (space1, "ago")).with_taken(),
+            move |(span, taken)| anchor.shift(-span).ok_or_else(|| overflow(taken)),
+        )
+        .map(|(dtime, zone)| TimeSpec::Point(dtime, zone)),
+        validate(
+            terminated(timespan!(), (space1, "left")).with_taken(),
+            move |(span, taken)| anchor.shift(span).ok_or_else(|| overflow(taken)),
+        )
+        .map(|(dtime, zone)| TimeSpec::Point(dtime, zone)),
+        (
+            opt(alt(("+", "-"))),
+            alt((
+                validate(literal("now"), move |taken| {
+                    anchor.shift(Duration::ZERO).ok_or_else(|| overflow(taken))
+                }),
+                literal("today").value((wall.date().midnight(), Zone::Local)),
+                literal("yesterday").value((yesterday(wall), Zone::Local)),
+                literal("tomorrow").value((tomorrow(wall), Zone::Local)),
+                preceded("@", epoch!()).map(|dtime| (dtime, Zone::Fixed(time::UtcOffset::UTC))),
+                (
+                    alt((
+                        validate(
+                            (
+                                terminated(weekday!().with_taken(), space1),
+                                cut_err(date_time!()),
+                            ),
+                            |((given, name), dtime): ((Weekday, &str), DateTime)| match dtime
+                                .weekday()
+                            {
+                                actual if actual == given => Ok(dtime),
+                                actual => Err(Failure::new(
+                                    FailureKind::WeekdayMismatch { given, actual },
+                                    0..name.len(),
+                                )),
+                            },
+                        ),
+                        date_time!(),
+                        time!().map(move |ptime| wall.replace_time(ptime)),
+                    )),
+                    opt(alt((
+                        preceded(space1, zone!()),
+                        one_of(['Z', 'z']).value(Zone::Fixed(time::UtcOffset::UTC)),
+                        utc_offset!().map(Zone::Fixed),
+                    ))),
+                )
+                    .map(|(dtime, zone)| (dtime, zone.unwrap_or_default())),
+            )),
+        )
+            .context(StrContext::Label("timespec"))
+            .map(|(modifier, (dtime, zone))| match modifier {
+                Some("+") => TimeSpec::After(dtime, zone),
+                Some("-") => TimeSpec::Before(dtime, zone),
+                None => TimeSpec::Point(dtime, zone),
+                _ => unreachable!(),
+            }),
+    ))
+}
@@ -0,0 +1,196 @@
+//! Multi-line rendering of errors, in the style of compiler diagnostics
+
+use crate::{error::Message, IntervalleError, Span};
+use std::{
+    fmt,
+    io::{self, IsTerminal},
+};
+
+const RED: &str = "\x1b[1;31m";
+const BLUE: &str = "\x1b[1;34m";
+const CYAN: &str = "\x1b[1;36m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+/// A diagnostic for an `IntervalleError`, underlining the offending part of the input
+pub struct Report<'a> {
+    error: &'a IntervalleError,
+    colour: bool,
+}
+
+impl IntervalleError {
+    /// Render the error as a diagnostic, coloured if standard error is a terminal
+    pub fn report(&self) -> Report<'_> {
+        Report {
+            error: self,
+            colour: io::stderr().is_terminal(),
+        }
+    }
+}
+
+impl Report<'_> {
+    /// Force ANSI colours on or off
+    pub fn colour(mut self, colour: bool) -> Self {
+        self.colour = colour;
+        self
+    }
+
+    fn paint(&self, style: &'static str) -> &'static str {
+        if self.colour {
+            style
+        } else {
+            ""
+        }
+    }
+
+    /// A short description of the span, written under it
+    fn label(&self) -> String {
+        match self.error {
+            IntervalleError::Syntax {
+                label: Some(label), ..
+            } => format!("invalid {label}"),
+            IntervalleError::Syntax { label: None, .. } => String::from("unexpected input"),
+            IntervalleError::UnknownKeyword { .. } => String::from("unknown keyword"),
+            IntervalleError::TrailingInput { .. } => String::from("unexpected trailing input"),
+            IntervalleError::InvalidMonth { .. } => String::from("not a month"),
+            IntervalleError::InvalidDay { month, year, .. } => {
+                format!("not a day of {month} {year}")
+            }
+            IntervalleError::InvalidTime { component, .. } => format!("not a valid {component}"),
+            IntervalleError::WeekdayMismatch { actual, .. } => format!("date is a {actual}"),
+            IntervalleError::Overflow { .. } => String::from("out of range"),
+            IntervalleError::SpanOverflow { .. } => String::from("too long"),
+            IntervalleError::InvalidCalendarValue { field, .. } => format!("not a valid {field}"),
+            _ => String::new(),
+        }
+    }
+
+    /// Notes on what the parser would have accepted instead
+    fn notes(&self) -> Vec<(&'static str, String)> {
+        let mut notes = vec![];
+
+        match self.error {
+            IntervalleError::Syntax { expected, .. } => {
+                notes.extend(expected.iter().map(|e| ("note", format!("expected {e}"))))
+            }
+            IntervalleError::UnknownKeyword {
+                expected,
+                suggestion: None,
+                ..
+            } => notes.push(("note", format!("expected one of {}", expected.join(", ")))),
+            IntervalleError::TrailingInput { closest, .. } if !closest.is_empty() => notes.push((
+                "help",
+                format!("closest accepted forms are `{}`", closest.join("`, `")),
+            )),
+            _ => (),
+        }
+
+        notes
+    }
+
+    fn snippet(&self, f: &mut fmt::Formatter, span: &Span) -> Result<(), fmt::Error> {
+        let (blue, red, reset) = (self.paint(BLUE), self.paint(RED), self.paint(RESET));
+        let column = span.input[..span.range.start].chars().count();
+        let width = span.text().chars().count().max(1);
+
+        writeln!(f, "{blue}  |{reset}")?;
+        writeln!(f, "{blue}  |{reset} {}", span.input)?;
+        writeln!(
+            f,
+            "{blue}  |{reset} {:column$}{red}{} {}{reset}",
+            "",
+            "^".repeat(width),
+            self.label()
+        )?;
+        writeln!(f, "{blue}  |{reset}")?;
+
+        for (kind, note) in self.notes() {
+            writeln!(
+                f,
+                "{blue}  ={reset} {}{kind}{reset}: {note}",
+                self.paint(BOLD)
+            )?;
+        }
+
+        if !span.formats.is_empty() {
+            writeln!(
+                f,
+                "{blue}  ={reset} {}help{reset}: accepted formats are",
+                self.paint(CYAN)
+            )?;
+            for format in span.formats {
+                writeln!(f, "          {format}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Report<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let (red, bold, reset) = (self.paint(RED), self.paint(BOLD), self.paint(RESET));
+        writeln!(f, "{red}error{reset}{bold}: {}{reset}", Message(self.error))?;
+
+        match self.error.span() {
+            Some(span) => self.snippet(f, span),
+            None => Ok(()),
+        }
+    }
+}
+
+#[test]
+fn test_report() {
+    let anchor = time::Date::from_calendar_date(2024, time::Month::August, 8)
+        .unwrap()
+        .midnight();
+
+    let error =
+        crate::TimeSpec::parse_with_anchor("2024-08-08 14:10:11 extra", anchor).unwrap_err();
+    let report = error.report().colour(false).to_string();
+    let mut lines = report.lines();
+
+    assert_eq!(
+        lines.next(),
+        Some(
+            "error: unexpected trailing input `extra`, closest accepted forms are `2024-08-08 14:10:11 UTC`"
+        )
+    );
+    assert_eq!(lines.next(), Some("  |"));
+    assert_eq!(lines.next(), Some("  | 2024-08-08 14:10:11 extra"));
+    assert_eq!(
+        lines.next(),
+        Some("  |                     ^^^^^ unexpected trailing input")
+    );
+    assert_eq!(lines.next(), Some("  |"));
+    assert_eq!(
+        lines.next(),
+        Some("  = help: closest accepted forms are `2024-08-08 14:10:11 UTC`")
+    );
+    assert_eq!(lines.next(), Some("  = help: accepted formats are"));
+    assert_eq!(lines.count(), crate::error::FORMATS.len());
+}
+
+#[test]
+fn test_report_colour() {
+    let anchor = time::Date::from_calendar_date(2024, time::Month::August, 8)
+        .unwrap()
+        .midnight();
+
+    let error = crate::TimeSpec::parse_with_anchor("yesturday", anchor).unwrap_err();
+
+    assert!(error.report().colour(true).to_string().contains(RED));
+    assert!(!error.report().colour(false).to_string().contains('\x1b'));
+
+    let report = error.report().colour(false).to_string();
+    assert_eq!(report.matches("did you mean 'yesterday'?").count(), 1);
+}
+
+#[test]
+fn test_report_grammar() {
+    let error = crate::CalendarEvent::parse("*-13-01").unwrap_err();
+    let report = error.report().colour(false).to_string();
+
+    assert!(report.contains("          YYYY-MM~DD for days counted from the end of the month"));
+    assert!(!report.contains("@<seconds since the epoch>"));
+}
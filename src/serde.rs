@@ -0,0 +1,239 @@
+//! Serialization of timespecs, intervals and spans as the strings they are parsed from
+//!
+//! Values deserialize from the human syntax their `parse` function accepts, with relative
+//! forms resolved against the time of loading, and serialize to their canonical form.
+
+use crate::{error::Message, Interval, IntervalleError, TimeSpan, TimeSpec};
+use ::serde::{
+    de::{self, Deserializer, Visitor},
+    Deserialize, Serialize, Serializer,
+};
+use std::{fmt, marker::PhantomData, str::FromStr, time::Duration};
+
+/// A deserialization error carrying the one-line description of `error`
+fn custom<E: de::Error>(error: IntervalleError) -> E {
+    E::custom(Message(&error))
+}
+
+/// Reads a value from a string through its `FromStr` implementation
+struct Parsed<T>(PhantomData<T>, &'static str);
+
+impl<T: FromStr<Err = IntervalleError>> Visitor<'_> for Parsed<T> {
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.1)
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<T, E> {
+        value.parse().map_err(custom)
+    }
+}
+
+impl Serialize for TimeSpec {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for TimeSpec {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(Parsed(PhantomData, "a timespec"))
+    }
+}
+
+impl Serialize for Interval {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Intervals are read from a single range, e.g. `yesterday..today`
+impl<'de> Deserialize<'de> for Interval {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(Parsed(PhantomData, "a range of timespecs"))
+    }
+}
+
+impl Serialize for TimeSpan {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Spans are read from a string, or from an integer number of seconds as in `TimeoutSec=90`
+///
+/// Only self-describing formats can tell the two apart: compact formats read the string alone.
+impl<'de> Deserialize<'de> for TimeSpan {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Span;
+
+        impl Visitor<'_> for Span {
+            type Value = TimeSpan;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "a time span or a number of seconds")
+            }
+
+            fn visit_str<E: de::Error>(self, span: &str) -> Result<TimeSpan, E> {
+                TimeSpan::parse(span).map_err(custom)
+            }
+
+            fn visit_u64<E: de::Error>(self, seconds: u64) -> Result<TimeSpan, E> {
+                Ok(TimeSpan::Finite(Duration::from_secs(seconds)))
+            }
+
+            fn visit_i64<E: de::Error>(self, seconds: i64) -> Result<TimeSpan, E> {
+                match u64::try_from(seconds) {
+                    Ok(seconds) => self.visit_u64(seconds),
+                    Err(_) => Err(E::invalid_value(de::Unexpected::Signed(seconds), &self)),
+                }
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(Span)
+        } else {
+            deserializer.deserialize_str(Span)
+        }
+    }
+}
+
+/// Read a timespec straight into the instant it designates, for use with
+/// `#[serde(with = "intervalle::serde::resolved")]`
+///
+/// Relative forms are resolved against the time of deserialization, and `Zone::Local` against
+/// the system's timezone. Instants are written back as a timestamp with their offset.
+///
+/// ```ignore
+/// #[derive(serde::Deserialize)]
+/// struct Retention {
+///     #[serde(with = "intervalle::serde::resolved")]
+///     since: time::OffsetDateTime,
+/// }
+/// ```
+pub mod resolved {
+    use super::custom;
+    use crate::{TimeSpec, Zone};
+    use ::serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use time::{OffsetDateTime, PrimitiveDateTime as DateTime};
+
+    pub fn serialize<S: Serializer>(
+        instant: &OffsetDateTime,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        TimeSpec::Point(
+            DateTime::new(instant.date(), instant.time()),
+            Zone::Fixed(instant.offset()),
+        )
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<OffsetDateTime, D::Error> {
+        TimeSpec::deserialize(deserializer)?
+            .resolve_local()
+            .map_err(custom)
+    }
+
+    /// The same as `resolved`, for optional fields
+    pub mod option {
+        use ::serde::{Deserialize, Deserializer, Serialize, Serializer};
+        use time::OffsetDateTime;
+
+        /// An instant read and written through `resolved`
+        struct Resolved(OffsetDateTime);
+
+        impl Serialize for Resolved {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                super::serialize(&self.0, serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for Resolved {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                super::deserialize(deserializer).map(Resolved)
+            }
+        }
+
+        pub fn serialize<S: Serializer>(
+            instant: &Option<OffsetDateTime>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            instant.map(Resolved).serialize(serializer)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Option<OffsetDateTime>, D::Error> {
+            Ok(Option::<Resolved>::deserialize(deserializer)?.map(|Resolved(instant)| instant))
+        }
+    }
+}
+
+#[test]
+fn test_serde_timespec() {
+    use serde_test::{assert_de_tokens_error, assert_tokens, Configure, Token};
+
+    let timespec = TimeSpec::Point(
+        time::Date::from_calendar_date(2024, time::Month::August, 8)
+            .unwrap()
+            .with_hms(14, 10, 0)
+            .unwrap(),
+        crate::Zone::Fixed(time::UtcOffset::UTC),
+    );
+    assert_tokens(&timespec, &[Token::Str("2024-08-08 14:10:00 UTC")]);
+
+    let span = TimeSpan::Finite(Duration::from_secs(5400));
+    assert_tokens(&span.readable(), &[Token::Str("1h 30min")]);
+    assert_tokens(&span.compact(), &[Token::Str("1h 30min")]);
+    assert_tokens(&TimeSpan::Infinity.readable(), &[Token::Str("infinity")]);
+    serde_test::assert_de_tokens(&span.readable(), &[Token::U64(5400)]);
+
+    assert_de_tokens_error::<TimeSpec>(
+        &[Token::Str("yesturday")],
+        "unknown keyword `yesturday`, did you mean 'yesterday'?",
+    );
+}
+
+#[test]
+fn test_serde_compact() {
+    let span = TimeSpan::Finite(Duration::from_secs(5400));
+    let bytes = bincode::serialize(&span).unwrap();
+    assert_eq!(bincode::deserialize::<TimeSpan>(&bytes).unwrap(), span);
+
+    let bytes = bincode::serialize(&TimeSpan::Infinity).unwrap();
+    assert_eq!(
+        bincode::deserialize::<TimeSpan>(&bytes).unwrap(),
+        TimeSpan::Infinity
+    );
+}
+
+#[test]
+fn test_serde_interval() {
+    use serde_test::{assert_tokens, Token};
+
+    let interval = Interval::parse_range("2024-08-08 10:00 UTC..").unwrap();
+    assert_tokens(&interval, &[Token::Str("2024-08-08 10:00:00 UTC..")]);
+}
+
+#[test]
+fn test_serde_resolved() {
+    use ::serde::de::{value::StrDeserializer, IntoDeserializer};
+
+    let deserializer: StrDeserializer<de::value::Error> =
+        "2024-08-08 14:10:00 +02:00".into_deserializer();
+    assert_eq!(
+        resolved::deserialize(deserializer).unwrap(),
+        time::Date::from_calendar_date(2024, time::Month::August, 8)
+            .unwrap()
+            .with_hms(12, 10, 0)
+            .unwrap()
+            .assume_utc()
+    );
+
+    let deserializer: StrDeserializer<de::value::Error> = "-1h".into_deserializer();
+    let instant = resolved::deserialize(deserializer).unwrap();
+    assert!(instant < time::OffsetDateTime::now_utc() - time::Duration::minutes(59));
+}
@@ -0,0 +1,226 @@
+use crate::{
+    error::{Failure, FailureKind, TIMESPAN},
+    parser::{self, nanoseconds, validate, NSEC_PER_SEC},
+    IntervalleError,
+};
+use std::{fmt, str::FromStr, time::Duration};
+use winnow::{
+    ascii::{digit1, space0},
+    combinator::{alt, opt, preceded, repeat},
+    error::{ContextError, StrContext},
+    prelude::*,
+};
+
+/// A time span as taken by systemd settings such as `TimeoutSec=`, e.g. `1h 30min`, `90` or
+/// `infinity`
+///
+/// Spans have the microsecond precision of systemd: finer parts are truncated. A number without
+/// a unit is a number of seconds.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy)]
+pub enum TimeSpan {
+    Finite(Duration),
+    Infinity,
+}
+
+/// Units written by `Display`, with their length in nanoseconds
+const UNITS: [(&str, i128); 9] = [
+    ("y", parser::NSEC_PER_YEAR),
+    ("month", parser::NSEC_PER_MONTH),
+    ("w", parser::NSEC_PER_WEEK),
+    ("d", parser::NSEC_PER_DAY),
+    ("h", parser::NSEC_PER_HOUR),
+    ("min", parser::NSEC_PER_MINUTE),
+    ("s", parser::NSEC_PER_SEC),
+    ("ms", parser::NSEC_PER_MSEC),
+    ("us", parser::NSEC_PER_USEC),
+];
+
+impl TimeSpan {
+    pub fn parse(span: &str) -> Result<Self, IntervalleError> {
+        parser::complete(timespan(), span, &TIMESPAN)
+    }
+
+    pub fn is_infinite(&self) -> bool {
+        matches!(self, TimeSpan::Infinity)
+    }
+
+    /// The length of the span, unless it is infinite
+    pub fn finite(self) -> Option<Duration> {
+        match self {
+            TimeSpan::Finite(duration) => Some(duration),
+            TimeSpan::Infinity => None,
+        }
+    }
+}
+
+impl FromStr for TimeSpan {
+    type Err = IntervalleError;
+
+    fn from_str(span: &str) -> Result<Self, Self::Err> {
+        TimeSpan::parse(span)
+    }
+}
+
+/// Writes the span the way systemd normalizes it, e.g. `1h 30min 30s` for `5430s`
+impl fmt::Display for TimeSpan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let mut rest = match self {
+            TimeSpan::Infinity => return write!(f, "infinity"),
+            TimeSpan::Finite(duration) if duration.is_zero() => return write!(f, "0"),
+            TimeSpan::Finite(duration) => duration.as_nanos() as i128,
+        };
+
+        let mut first = true;
+        for (unit, length) in UNITS {
+            let count = rest / length;
+            if count > 0 {
+                write!(f, "{}{count}{unit}", if first { "" } else { " " })?;
+                first = false;
+            }
+            rest %= length;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<Duration> for TimeSpan {
+    fn from(duration: Duration) -> Self {
+        TimeSpan::Finite(Duration::new(
+            duration.as_secs(),
+            duration.subsec_micros() * 1_000,
+        ))
+    }
+}
+
+/// An infinite span is the longest `Duration`
+impl From<TimeSpan> for Duration {
+    fn from(span: TimeSpan) -> Self {
+        span.finite().unwrap_or(Duration::MAX)
+    }
+}
+
+/// An infinite span, or a finite one too long to be represented, is the longest `Duration`
+impl From<TimeSpan> for time::Duration {
+    fn from(span: TimeSpan) -> Self {
+        span.finite()
+            .and_then(|duration| time::Duration::try_from(duration).ok())
+            .unwrap_or(time::Duration::MAX)
+    }
+}
+
+/// A `<number>[.<fraction>][<unit>]` component of a time span, in nanoseconds, the unit being
+/// seconds when left out as systemd does
+fn component<'i>() -> impl Parser<&'i str, Option<i128>, ContextError> {
+    (
+        digit1,
+        opt(preceded(".", digit1)),
+        opt(preceded(space0, unit!())),
+    )
+        .map(|(int, frac, unit)| nanoseconds(int, frac, unit.unwrap_or(NSEC_PER_SEC)))
+}
+
+/// `infinity`, or a time span made of one or more components, e.g. `1h 30min`, `90` or `1h 30`
+fn timespan<'i>() -> impl Parser<&'i str, TimeSpan, ContextError> {
+    alt((
+        "infinity".value(TimeSpan::Infinity),
+        validate(
+            (
+                component(),
+                repeat(0.., preceded(space0, component())).fold(
+                    || Some(0i128),
+                    |acc: Option<i128>, n: Option<i128>| acc?.checked_add(n?),
+                ),
+            )
+                .with_taken(),
+            |((first, rest), taken): ((Option<i128>, Option<i128>), &str)| {
+                first
+                    .zip(rest)
+                    .and_then(|(first, rest)| first.checked_add(rest))
+                    .and_then(|total| {
+                        let seconds = u64::try_from(total / NSEC_PER_SEC).ok()?;
+                        Some(Duration::new(seconds, (total % NSEC_PER_SEC) as u32))
+                    })
+                    .map(TimeSpan::from)
+                    .ok_or_else(|| Failure::new(FailureKind::SpanOverflow, 0..taken.len()))
+            },
+        ),
+    ))
+    .context(StrContext::Label("time span"))
+}
+
+#[test]
+fn test_timespan_parse() {
+    for (span, expected) in [
+        ("90", Duration::from_secs(90)),
+        ("1.5", Duration::from_millis(1500)),
+        ("1h 30min", Duration::from_secs(5400)),
+        ("2 days", Duration::from_secs(2 * 86_400)),
+        ("500ms", Duration::from_millis(500)),
+        ("1.0000005s", Duration::from_secs(1)),
+        ("0", Duration::ZERO),
+        ("1h 30", Duration::from_secs(3630)),
+        ("1h30", Duration::from_secs(3630)),
+        ("1 30min", Duration::from_secs(1801)),
+        ("31557600000", Duration::from_secs(31_557_600_000)),
+        ("1000y", Duration::from_secs(31_557_600_000)),
+    ] {
+        assert_eq!(
+            TimeSpan::parse(span).unwrap(),
+            TimeSpan::Finite(expected),
+            "{span}"
+        );
+    }
+
+    assert_eq!("infinity".parse::<TimeSpan>().unwrap(), TimeSpan::Infinity);
+
+    for span in ["", "-1h", "1 parsec", "infinity 1s", "1h infinity", "1.5.5"] {
+        assert!(TimeSpan::parse(span).is_err(), "{span}");
+    }
+
+    match TimeSpan::parse("1h 600000000000y") {
+        Err(IntervalleError::SpanOverflow { span }) => assert_eq!(span.range, 0..16),
+        other => panic!("unexpected result: {other:?}"),
+    }
+}
+
+#[test]
+fn test_timespan_display() {
+    for (span, normalized) in [
+        ("5430s", "1h 30min 30s"),
+        ("1h 30min 5s", "1h 30min 5s"),
+        ("90", "1min 30s"),
+        ("1.5", "1s 500ms"),
+        ("36h", "1d 12h"),
+        ("1y 1M", "1y 1month"),
+        ("2weeks 3d", "2w 3d"),
+        ("1500us", "1ms 500us"),
+        ("0", "0"),
+        ("0min", "0"),
+        ("infinity", "infinity"),
+    ] {
+        let parsed = TimeSpan::parse(span).unwrap();
+        assert_eq!(parsed.to_string(), normalized, "{span}");
+        assert_eq!(TimeSpan::parse(normalized).unwrap(), parsed, "{span}");
+    }
+}
+
+#[test]
+fn test_timespan_conversions() {
+    let span = TimeSpan::parse("1min 30s").unwrap();
+
+    assert_eq!(Duration::from(span), Duration::from_secs(90));
+    assert_eq!(time::Duration::from(span), time::Duration::seconds(90));
+    assert_eq!(
+        TimeSpan::from(Duration::from_nanos(1_500)),
+        TimeSpan::parse("1us").unwrap()
+    );
+
+    assert_eq!(Duration::from(TimeSpan::Infinity), Duration::MAX);
+    assert_eq!(
+        time::Duration::from(TimeSpan::Infinity),
+        time::Duration::MAX
+    );
+    assert_eq!(TimeSpan::Infinity.finite(), None);
+    assert!(TimeSpan::Infinity > span);
+}
@@ -0,0 +1,202 @@
+use crate::IntervalleError;
+use std::{fmt, fs, io, path::Path};
+use time::{OffsetDateTime, PrimitiveDateTime as DateTime, UtcOffset};
+use tz::error::{TzError, TzStringError};
+
+/// The directories the IANA timezone database may be installed in, as searched by tz-rs
+const ZONEINFO: [&str; 3] = ["/usr/share/zoneinfo", "/share/zoneinfo", "/etc/zoneinfo"];
+
+/// The timezone a `TimeSpec` was expressed in
+#[derive(PartialEq, Debug, Clone, Default)]
+pub enum Zone {
+    /// The system's local timezone, used when no timezone is given
+    #[default]
+    Local,
+    /// A fixed offset from UTC, given as `UTC`, `Z` or a numeric offset like `+02:00` or
+    /// `+00:09:21`
+    Fixed(UtcOffset),
+    /// A timezone from the IANA database, e.g. `Europe/Paris`
+    Named(String),
+}
+
+/// Where a wall clock time falls on the timeline
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Resolution {
+    /// The wall clock time designates a single instant
+    Single(OffsetDateTime),
+    /// The wall clock time occurs twice as clocks are set back, at both of these instants
+    Ambiguous(OffsetDateTime, OffsetDateTime),
+    /// The wall clock time is skipped as clocks are set forward. The instants are obtained by
+    /// reading it with the offset from after, then before, the transition.
+    Nonexistent(OffsetDateTime, OffsetDateTime),
+}
+
+/// How to place wall clock times that a DST transition skips or repeats
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum Disambiguation {
+    /// Use the earliest candidate instant
+    #[default]
+    Earliest,
+    /// Use the latest candidate instant
+    Latest,
+    /// Fail with `IntervalleError::NonexistentLocalTime` or `IntervalleError::AmbiguousLocalTime`
+    Reject,
+}
+
+impl Resolution {
+    /// The earliest instant the wall clock time may designate
+    pub fn earliest(self) -> OffsetDateTime {
+        match self {
+            Resolution::Single(instant)
+            | Resolution::Ambiguous(instant, _)
+            | Resolution::Nonexistent(instant, _) => instant,
+        }
+    }
+
+    /// The latest instant the wall clock time may designate
+    pub fn latest(self) -> OffsetDateTime {
+        match self {
+            Resolution::Single(instant)
+            | Resolution::Ambiguous(_, instant)
+            | Resolution::Nonexistent(_, instant) => instant,
+        }
+    }
+}
+
+impl Zone {
+    /// Load the timezone rules for this zone
+    pub fn time_zone(&self) -> Result<tz::TimeZone, tz::error::TzError> {
+        match self {
+            Zone::Local => tz::TimeZone::local(),
+            Zone::Fixed(offset) => Ok(tz::TimeZone::fixed(offset.whole_seconds())?),
+            Zone::Named(name) => load(name),
+        }
+    }
+
+    /// Write the zone as it follows a timestamp, preceded by a space, or nothing for
+    /// `Zone::Local`
+    pub(crate) fn write_suffix(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            Zone::Local => Ok(()),
+            Zone::Fixed(offset) if offset.is_utc() => write!(f, " UTC"),
+            Zone::Fixed(offset) => {
+                write!(
+                    f,
+                    " {}{:02}:{:02}",
+                    if offset.is_negative() { '-' } else { '+' },
+                    offset.whole_hours().abs(),
+                    offset.minutes_past_hour().abs()
+                )?;
+
+                match offset.seconds_past_minute() {
+                    0 => Ok(()),
+                    seconds => write!(f, ":{:02}", seconds.abs()),
+                }
+            }
+            Zone::Named(name) => write!(f, " {name}"),
+        }
+    }
+
+    /// Find the wall clock time `dtime` on the timeline, with `local` standing in for
+    /// `Zone::Local`
+    pub(crate) fn locate(
+        &self,
+        dtime: DateTime,
+        local: &tz::TimeZone,
+    ) -> Result<Resolution, IntervalleError> {
+        match self {
+            Zone::Fixed(offset) => Ok(Resolution::Single(dtime.assume_offset(*offset))),
+            Zone::Local => locate(local, dtime),
+            Zone::Named(_) => locate(
+                &self
+                    .time_zone()
+                    .map_err(|e| IntervalleError::TimeZoneError(e.into()))?,
+                dtime,
+            ),
+        }
+    }
+}
+
+/// Whether `name` designates a file within the zoneinfo directory, e.g. `Europe/Paris`,
+/// `US/Eastern` or `EST5EDT`: a relative path without `.` or `..` components
+fn is_zone_name(name: &str) -> bool {
+    name.split('/')
+        .all(|component| !matches!(component, "" | "." | ".."))
+}
+
+/// Load the timezone `name` from the zoneinfo directory
+///
+/// Unlike `tz::TimeZone::from_posix_tz`, absolute paths and POSIX rules such as `EST5` are not
+/// accepted, nothing is read outside of the zoneinfo directory, and files that do not hold
+/// TZif data, such as `zone.tab`, are rejected.
+pub(crate) fn load(name: &str) -> Result<tz::TimeZone, TzError> {
+    if !is_zone_name(name) {
+        return Err(TzError::TzStringError(TzStringError::InvalidTzString(
+            "not a timezone name",
+        )));
+    }
+
+    let mut error = io::Error::from(io::ErrorKind::NotFound);
+    for directory in ZONEINFO {
+        match fs::read(Path::new(directory).join(name)) {
+            Ok(data) => return tz::TimeZone::from_tz_data(&data),
+            Err(e) => error = e,
+        }
+    }
+
+    Err(TzError::IoError(error))
+}
+
+/// Offset from UTC observed by `time_zone` at the Unix timestamp `instant`
+pub(crate) fn instant_offset(
+    time_zone: &tz::TimeZone,
+    instant: i64,
+) -> Result<UtcOffset, IntervalleError> {
+    let local_time_type = time_zone
+        .find_local_time_type(instant)
+        .map_err(|e| IntervalleError::TimeZoneError(e.into()))?;
+
+    UtcOffset::from_whole_seconds(local_time_type.ut_offset())
+        .map_err(|e| IntervalleError::TimeZoneError(e.into()))
+}
+
+/// Find the wall clock time `dtime` on the timeline of `time_zone`
+///
+/// The offsets observed a day either side of `dtime` are the only candidates, as transitions
+/// are never that close together. A candidate applies if reading `dtime` with it designates an
+/// instant at which it is in effect: both apply in a fold, neither does in a gap.
+pub(crate) fn locate(
+    time_zone: &tz::TimeZone,
+    dtime: DateTime,
+) -> Result<Resolution, IntervalleError> {
+    const DAY: i64 = 86_400;
+
+    let naive = dtime.assume_utc().unix_timestamp();
+
+    let mut candidates = [
+        instant_offset(time_zone, naive - DAY)?,
+        instant_offset(time_zone, naive + DAY)?,
+    ]
+    .map(|offset| dtime.assume_offset(offset));
+    candidates.sort();
+
+    let mut valid = Vec::with_capacity(2);
+    for candidate in candidates {
+        let observed = instant_offset(time_zone, candidate.unix_timestamp())?;
+
+        if observed == candidate.offset() && !valid.contains(&candidate) {
+            valid.push(candidate);
+        }
+    }
+
+    let [earliest, latest] = candidates;
+
+    Ok(match valid[..] {
+        [instant] => Resolution::Single(instant),
+        [earliest, latest] => Resolution::Ambiguous(earliest, latest),
+        _ => Resolution::Nonexistent(
+            earliest.to_offset(instant_offset(time_zone, earliest.unix_timestamp())?),
+            latest.to_offset(instant_offset(time_zone, latest.unix_timestamp())?),
+        ),
+    })
+}